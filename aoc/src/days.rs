@@ -0,0 +1,53 @@
+use anyhow::{anyhow, Result};
+
+#[derive(Debug, Copy, Clone)]
+pub struct Day {
+    pub number: u8,
+    pub dir: &'static str,
+    pub package: &'static str,
+    /// Whether the day's binary prints a rendering when passed `--render`.
+    pub render: bool,
+}
+
+impl Day {
+    const fn new(number: u8, dir: &'static str, package: &'static str) -> Self {
+        Self {
+            number,
+            dir,
+            package,
+            render: false,
+        }
+    }
+
+    const fn rendered(self) -> Self {
+        Self {
+            render: true,
+            ..self
+        }
+    }
+}
+
+pub const DAYS: &[Day] = &[
+    Day::new(1, "day01", "day1"),
+    Day::new(2, "day02", "day2"),
+    Day::new(3, "day03", "day3"),
+    Day::new(4, "day04", "day4"),
+    Day::new(5, "day05", "day5").rendered(),
+    Day::new(6, "day06", "day6"),
+    Day::new(7, "day07", "day7"),
+    Day::new(8, "day08", "day8").rendered(),
+    Day::new(9, "day09", "day9"),
+    Day::new(10, "day10", "day10").rendered(),
+    Day::new(11, "day11", "day11"),
+    Day::new(12, "day12", "day12").rendered(),
+    Day::new(13, "day13", "day13"),
+    Day::new(14, "day14", "day14").rendered(),
+    Day::new(15, "day15", "day15").rendered(),
+];
+
+pub fn find_day(number: u8) -> Result<Day> {
+    DAYS.iter()
+        .find(|d| d.number == number)
+        .copied()
+        .ok_or_else(|| anyhow!("Day {} is not registered with the runner", number))
+}
@@ -0,0 +1,54 @@
+mod days;
+mod report;
+mod scaffold;
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use anyhow::{anyhow, Result};
+
+const USAGE: &str = "Usage: aoc <command>
+
+Commands:
+    new <day>    Generate a new `dayNN` crate and register it
+    run <day>    Build and run a registered day in release mode
+    report [out] Write an HTML report of every day (default: report.html)";
+
+fn parse_day(arg: Option<String>) -> Result<u8> {
+    let arg = arg.ok_or_else(|| anyhow!("Expected day number\n\n{}", USAGE))?;
+    arg.parse::<u8>()
+        .map_err(|e| anyhow!("Could not parse day number {}: {}", arg, e))
+}
+
+fn run_day(number: u8) -> Result<()> {
+    let day = days::find_day(number)?;
+    let status = Command::new("cargo")
+        .args(["run", "--release", "-p", day.package])
+        .current_dir(scaffold::workspace_root()?.join(day.dir))
+        .status()?;
+    if !status.success() {
+        return Err(anyhow!("{} exited with {}", day.package, status));
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("new") => scaffold::new_day(&scaffold::workspace_root()?, parse_day(args.next())?),
+        Some("run") => run_day(parse_day(args.next())?),
+        Some("report") => {
+            let root = scaffold::workspace_root()?;
+            let out = args
+                .next()
+                .map(PathBuf::from)
+                .unwrap_or_else(|| root.join("report.html"));
+            report::write_report(&root, &out)
+        }
+        Some(c) => Err(anyhow!("Unknown command: {}\n\n{}", c, USAGE)),
+        None => {
+            println!("{USAGE}");
+            Ok(())
+        }
+    }
+}
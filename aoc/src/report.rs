@@ -0,0 +1,234 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use crate::days::{Day, DAYS};
+
+const BENCH_RUNS: usize = 5;
+
+const STYLE: &str =
+    "body { font-family: sans-serif; max-width: 960px; margin: 2em auto; color: #222; }
+section { border-top: 1px solid #ccc; padding: 0.5em 0; }
+table { border-collapse: collapse; }
+td, th { padding: 0.2em 1em 0.2em 0; text-align: left; }
+pre { background: #10101a; color: #e0e0e0; padding: 0.5em; overflow-x: auto; line-height: 1.1; }
+.passed { color: #1a7f37; }
+.failed { color: #cf222e; }";
+
+#[derive(Debug, Clone)]
+struct DayReport {
+    day: Day,
+    answers: Vec<(String, String)>,
+    best: Duration,
+    mean: Duration,
+    run_error: Option<String>,
+    tests_passed: bool,
+    rendering: Option<String>,
+}
+
+fn target_dir(root: &Path) -> PathBuf {
+    std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| root.join("target"))
+}
+
+fn extract_answers(stdout: &str) -> Vec<(String, String)> {
+    stdout
+        .lines()
+        .filter_map(|l| {
+            let (label, answer) = l.split_once(':')?;
+            matches!(label, "Part one" | "Part two")
+                .then(|| (label.to_owned(), answer.trim().to_owned()))
+        })
+        .collect()
+}
+
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn report_day(root: &Path, day: Day) -> Result<DayReport> {
+    let binary = target_dir(root).join("release").join(day.package);
+    let day_dir = root.join(day.dir);
+
+    let mut timings = Vec::with_capacity(BENCH_RUNS);
+    let mut stdout = String::new();
+    let mut run_error = None;
+    for _ in 0..BENCH_RUNS {
+        let start = Instant::now();
+        let output = Command::new(&binary)
+            .current_dir(&day_dir)
+            .stderr(Stdio::null())
+            .output()?;
+        timings.push(start.elapsed());
+        if !output.status.success() {
+            run_error = Some(format!("exited with {}", output.status));
+            break;
+        }
+        stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    }
+
+    let tests_passed = Command::new("cargo")
+        .args(["test", "--release", "-p", day.package])
+        .current_dir(&day_dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?
+        .success();
+
+    let rendering = if day.render && run_error.is_none() {
+        let output = Command::new(&binary)
+            .arg("--render")
+            .current_dir(&day_dir)
+            .stderr(Stdio::null())
+            .output()?;
+        Some(
+            String::from_utf8_lossy(&output.stdout)
+                .trim_matches('\n')
+                .to_owned(),
+        )
+    } else {
+        None
+    };
+
+    Ok(DayReport {
+        day,
+        answers: extract_answers(&stdout),
+        best: timings.iter().min().copied().unwrap_or_default(),
+        mean: timings.iter().sum::<Duration>() / timings.len().max(1) as u32,
+        run_error,
+        tests_passed,
+        rendering,
+    })
+}
+
+fn render_html(reports: &[DayReport]) -> Result<String> {
+    let mut html = String::new();
+    writeln!(html, "<!DOCTYPE html>")?;
+    writeln!(html, "<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(html, "<title>AoC 2022 report</title>")?;
+    writeln!(html, "<style>\n{}\n</style>\n</head>\n<body>", STYLE)?;
+    writeln!(html, "<h1>AoC 2022 report</h1>")?;
+    for report in reports {
+        writeln!(
+            html,
+            "<section id=\"{}\">\n<h2>Day {}</h2>",
+            report.day.dir, report.day.number
+        )?;
+        writeln!(html, "<table>")?;
+        if let Some(e) = &report.run_error {
+            writeln!(
+                html,
+                "<tr><th>Run</th><td class=\"failed\">{}</td></tr>",
+                escape_html(e)
+            )?;
+        }
+        for (label, answer) in report.answers.iter() {
+            let answer = if answer.is_empty() {
+                "(see rendering)"
+            } else {
+                answer
+            };
+            writeln!(
+                html,
+                "<tr><th>{}</th><td><code>{}</code></td></tr>",
+                escape_html(label),
+                escape_html(answer)
+            )?;
+        }
+        writeln!(
+            html,
+            "<tr><th>Time</th><td>best {:.2?}, mean {:.2?} over {} runs</td></tr>",
+            report.best, report.mean, BENCH_RUNS
+        )?;
+        let (class, status) = if report.tests_passed {
+            ("passed", "passed")
+        } else {
+            ("failed", "failed")
+        };
+        writeln!(
+            html,
+            "<tr><th>Tests</th><td class=\"{}\">{}</td></tr>",
+            class, status
+        )?;
+        writeln!(html, "</table>")?;
+        if let Some(r) = &report.rendering {
+            writeln!(html, "<pre>{}</pre>", escape_html(r))?;
+        }
+        writeln!(html, "</section>")?;
+    }
+    writeln!(html, "</body>\n</html>")?;
+    Ok(html)
+}
+
+/// Build every registered day in release mode, then run, time, test and render each of them
+/// and write the results to a single self-contained HTML page.
+pub fn write_report(root: &Path, out: &Path) -> Result<()> {
+    let status = Command::new("cargo")
+        .args(["build", "--release", "--workspace"])
+        .current_dir(root)
+        .status()?;
+    if !status.success() {
+        return Err(anyhow!("Release build failed with {}", status));
+    }
+    let mut reports = Vec::new();
+    for &day in DAYS {
+        eprintln!("Reporting on {}", day.dir);
+        reports.push(report_day(root, day)?);
+    }
+    std::fs::write(out, render_html(&reports)?)?;
+    println!("Wrote {}", out.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::days::DAYS;
+    use crate::report::{escape_html, extract_answers, render_html, DayReport};
+
+    #[test]
+    fn test_extract_answers() {
+        let stdout = "Cursor: Eof\nPart one: 95437\nPart two:\n\n#..#\n";
+        assert_eq!(
+            vec![
+                ("Part one".to_owned(), "95437".to_owned()),
+                ("Part two".to_owned(), "".to_owned())
+            ],
+            extract_answers(stdout)
+        );
+    }
+
+    #[test]
+    fn test_render_html() {
+        assert_eq!("&lt;v&gt; &amp; &quot;", escape_html("<v> & \""));
+        let report = DayReport {
+            day: DAYS[11],
+            answers: vec![("Part one".to_owned(), "31".to_owned())],
+            best: Duration::from_millis(3),
+            mean: Duration::from_millis(4),
+            run_error: None,
+            tests_passed: true,
+            rendering: Some(">>v\n^<E".to_owned()),
+        };
+        let html = render_html(&[report]).unwrap();
+        assert!(html.contains("<h2>Day 12</h2>"));
+        assert!(html.contains("<code>31</code>"));
+        assert!(html.contains("<pre>&gt;&gt;v\n^&lt;E</pre>"));
+        assert!(!html.contains("http"));
+    }
+}
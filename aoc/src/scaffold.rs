@@ -0,0 +1,153 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+const CARGO_TEMPLATE: &str = include_str!("templates/Cargo.toml.tmpl");
+const LIB_TEMPLATE: &str = include_str!("templates/lib.rs.tmpl");
+const MAIN_TEMPLATE: &str = include_str!("templates/main.rs.tmpl");
+const RUNNER_REGISTRY: &str = "aoc/src/days.rs";
+
+#[derive(Debug, Clone)]
+struct NewDay {
+    number: u8,
+    dir: String,
+    package: String,
+}
+
+impl NewDay {
+    fn new(number: u8) -> Result<Self> {
+        if !(1..=25).contains(&number) {
+            return Err(anyhow!("Day must be between 1 and 25, got {}", number));
+        }
+        Ok(Self {
+            number,
+            dir: format!("day{:02}", number),
+            package: format!("day{}", number),
+        })
+    }
+
+    fn render(&self, template: &str) -> String {
+        template
+            .replace("{{package}}", &self.package)
+            .replace("{{dir}}", &self.dir)
+    }
+}
+
+/// Walk up from the current directory to the manifest that declares the workspace.
+pub fn workspace_root() -> Result<PathBuf> {
+    let cwd = std::env::current_dir()?;
+    for dir in cwd.ancestors() {
+        let manifest = dir.join("Cargo.toml");
+        if manifest.is_file() && std::fs::read_to_string(&manifest)?.contains("[workspace]") {
+            return Ok(dir.to_path_buf());
+        }
+    }
+    Err(anyhow!(
+        "No workspace `Cargo.toml` found above {}",
+        cwd.display()
+    ))
+}
+
+pub fn new_day(root: &Path, number: u8) -> Result<()> {
+    let day = NewDay::new(number)?;
+    let day_dir = root.join(&day.dir);
+    if day_dir.exists() {
+        return Err(anyhow!("{} already exists", day_dir.display()));
+    }
+    std::fs::create_dir_all(day_dir.join("src"))?;
+    std::fs::write(day_dir.join("Cargo.toml"), day.render(CARGO_TEMPLATE))?;
+    std::fs::write(day_dir.join("src/lib.rs"), day.render(LIB_TEMPLATE))?;
+    std::fs::write(day_dir.join("src/main.rs"), day.render(MAIN_TEMPLATE))?;
+    std::fs::write(day_dir.join("src/input.txt"), "")?;
+    std::fs::write(day_dir.join("src/test_input.txt"), "")?;
+
+    let manifest_path = root.join("Cargo.toml");
+    let manifest = std::fs::read_to_string(&manifest_path)?;
+    std::fs::write(&manifest_path, register_member(&manifest, &day.dir)?)?;
+
+    let registry_path = root.join(RUNNER_REGISTRY);
+    let registry = std::fs::read_to_string(&registry_path)?;
+    std::fs::write(&registry_path, register_day(&registry, &day)?)?;
+
+    println!("Created {} (package `{}`)", day.dir, day.package);
+    Ok(())
+}
+
+fn register_member(manifest: &str, dir: &str) -> Result<String> {
+    let entry = format!("\"{}\",", dir);
+    if manifest.lines().any(|l| l.trim() == entry) {
+        return Ok(manifest.to_owned());
+    }
+    let start = manifest
+        .find("members = [")
+        .ok_or_else(|| anyhow!("Workspace manifest has no `members` list"))?;
+    let end = start
+        + manifest[start..]
+            .find("\n]")
+            .ok_or_else(|| anyhow!("Workspace `members` list is not terminated"))?;
+    Ok(format!(
+        "{}\n    {}{}",
+        &manifest[..end],
+        entry,
+        &manifest[end..]
+    ))
+}
+
+fn register_day(registry: &str, day: &NewDay) -> Result<String> {
+    let entry = format!(
+        "Day::new({}, \"{}\", \"{}\"),",
+        day.number, day.dir, day.package
+    );
+    if registry.lines().any(|l| l.trim() == entry) {
+        return Ok(registry.to_owned());
+    }
+    let start = registry
+        .find("pub const DAYS: &[Day] = &[")
+        .ok_or_else(|| anyhow!("Runner registry has no `DAYS` table"))?;
+    let end = start
+        + registry[start..]
+            .find("\n];")
+            .ok_or_else(|| anyhow!("Runner `DAYS` table is not terminated"))?;
+    Ok(format!(
+        "{}\n    {}{}",
+        &registry[..end],
+        entry,
+        &registry[end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scaffold::{register_day, register_member, NewDay, LIB_TEMPLATE, MAIN_TEMPLATE};
+
+    #[test]
+    fn test_render_template() {
+        let day = NewDay::new(16).unwrap();
+        let lib = day.render(LIB_TEMPLATE);
+        assert!(lib.contains("todo!(\"day16 part one\")"));
+        assert!(lib.contains("fn test_part_one()"));
+        assert!(lib.contains("fn test_part_two()"));
+        assert!(!lib.contains("{{"));
+        let main = day.render(MAIN_TEMPLATE);
+        assert!(main.contains("use day16::{part_one, part_two, read_input};"));
+        assert!(!main.contains("{{"));
+        assert!(NewDay::new(26).is_err());
+    }
+
+    #[test]
+    fn test_registration() {
+        let day = NewDay::new(16).unwrap();
+        let manifest = "[workspace]\nmembers = [\n    \"day15\",\n]\n";
+        let updated = register_member(manifest, &day.dir).unwrap();
+        assert_eq!(
+            "[workspace]\nmembers = [\n    \"day15\",\n    \"day16\",\n]\n",
+            updated
+        );
+        assert_eq!(updated, register_member(&updated, &day.dir).unwrap());
+
+        let registry = "pub const DAYS: &[Day] = &[\n    Day::new(15, \"day15\", \"day15\"),\n];\n";
+        let updated = register_day(registry, &day).unwrap();
+        assert!(updated.ends_with("    Day::new(16, \"day16\", \"day16\"),\n];\n"));
+        assert_eq!(updated, register_day(&updated, &day).unwrap());
+    }
+}
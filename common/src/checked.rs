@@ -0,0 +1,151 @@
+//! Arithmetic that can be switched to overflow-checked mode.
+//!
+//! Solvers route their accumulations and multiplications through these helpers. Without the
+//! `checked` feature they compile down to the plain operators, so debug builds panic and release
+//! builds wrap exactly as before. With the feature enabled every operation is checked and an
+//! overflow comes back as an [`OverflowError`] naming the day and the operation.
+
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Mul, Sub};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverflowError {
+    pub day: &'static str,
+    pub operation: &'static str,
+    pub detail: String,
+}
+
+impl Display for OverflowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: integer overflow in {} ({})",
+            self.day, self.operation, self.detail
+        )
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+pub trait CheckedInt:
+    Copy + Debug + Display + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_checked_int {
+    ($($t:ty),*) => {
+        $(
+            impl CheckedInt for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_checked_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+fn apply<T: CheckedInt>(
+    day: &'static str,
+    operation: &'static str,
+    (a, symbol, b): (T, &str, T),
+    checked: impl FnOnce(T, T) -> Option<T>,
+    unchecked: impl FnOnce(T, T) -> T,
+) -> Result<T, OverflowError> {
+    if cfg!(feature = "checked") {
+        checked(a, b).ok_or_else(|| OverflowError {
+            day,
+            operation,
+            detail: format!("{} {} {}", a, symbol, b),
+        })
+    } else {
+        Ok(unchecked(a, b))
+    }
+}
+
+pub fn add<T: CheckedInt>(
+    day: &'static str,
+    operation: &'static str,
+    a: T,
+    b: T,
+) -> Result<T, OverflowError> {
+    apply(day, operation, (a, "+", b), T::checked_add, T::add)
+}
+
+pub fn sub<T: CheckedInt>(
+    day: &'static str,
+    operation: &'static str,
+    a: T,
+    b: T,
+) -> Result<T, OverflowError> {
+    apply(day, operation, (a, "-", b), T::checked_sub, T::sub)
+}
+
+pub fn mul<T: CheckedInt>(
+    day: &'static str,
+    operation: &'static str,
+    a: T,
+    b: T,
+) -> Result<T, OverflowError> {
+    apply(day, operation, (a, "*", b), T::checked_mul, T::mul)
+}
+
+pub fn sum<T: CheckedInt>(
+    day: &'static str,
+    operation: &'static str,
+    values: impl IntoIterator<Item = T>,
+) -> Result<T, OverflowError> {
+    values
+        .into_iter()
+        .try_fold(T::ZERO, |acc, v| add(day, operation, acc, v))
+}
+
+pub fn product<T: CheckedInt>(
+    day: &'static str,
+    operation: &'static str,
+    values: impl IntoIterator<Item = T>,
+) -> Result<T, OverflowError> {
+    values
+        .into_iter()
+        .try_fold(T::ONE, |acc, v| mul(day, operation, acc, v))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::checked::{add, mul, product, sub, sum};
+
+    #[test]
+    fn test_in_range() {
+        assert_eq!(Ok(7u8), add("day0", "test", 3, 4));
+        assert_eq!(Ok(-1isize), sub("day0", "test", 3, 4));
+        assert_eq!(Ok(12usize), mul("day0", "test", 3, 4));
+        assert_eq!(Ok(10u64), sum("day0", "test", [1, 2, 3, 4]));
+        assert_eq!(Ok(24u64), product("day0", "test", [1, 2, 3, 4]));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        let e = mul("day11", "worry level `old * old`", u64::MAX, 2).unwrap_err();
+        assert_eq!(
+            "day11: integer overflow in worry level `old * old` (18446744073709551615 * 2)",
+            e.to_string()
+        );
+        assert!(sum("day2", "total score", [u8::MAX, 1]).is_err());
+        assert!(sub("day7", "unused space", 0usize, 1).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+//! Seeded input mutation for robustness tests.
+//!
+//! [`check_parser`] feeds a parser many mutated copies of a known-good input and fails with the
+//! seed, iteration and offending input if the parser panics instead of returning an `Err`.
+//! Everything is deterministic for a given seed so failures can be replayed.
+
+use std::fmt::Display;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::rng::Rng;
+
+/// Fragments that tend to upset hand-written parsers.
+const FRAGMENTS: &[&str] = &[
+    "",
+    " ",
+    "\n",
+    "\n\n",
+    "\t",
+    "0",
+    "-1",
+    "99999999999999999999",
+    "18446744073709551615",
+    "[",
+    "]",
+    ",",
+    "-",
+    ":",
+    "=",
+    "$",
+    "$ ",
+    "->",
+    "é",
+    "🎄",
+];
+
+/// Mutates text inputs with edits drawn from a seeded [`Rng`].
+#[derive(Debug, Clone)]
+pub struct Mutator {
+    rng: Rng,
+}
+
+impl Mutator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+        }
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        self.rng.below(n)
+    }
+
+    /// Apply between one and four random edits to `input`.
+    pub fn mutate(&mut self, input: &str) -> String {
+        let mut chars = input.chars().collect::<Vec<char>>();
+        for _ in 0..1 + self.below(4) {
+            match self.below(7) {
+                0 => {
+                    let start = self.below(chars.len() + 1);
+                    let end = (start + 1 + self.below(8)).min(chars.len());
+                    chars.drain(start..end);
+                }
+                1 => {
+                    let at = self.below(chars.len() + 1);
+                    let fragment = FRAGMENTS[self.below(FRAGMENTS.len())];
+                    chars.splice(at..at, fragment.chars());
+                }
+                2 => {
+                    if !chars.is_empty() {
+                        let at = self.below(chars.len());
+                        chars[at] = (b' ' + self.below(95) as u8) as char;
+                    }
+                }
+                3 => {
+                    let at = self.below(chars.len() + 1);
+                    chars.truncate(at);
+                }
+                op => {
+                    let mut lines = String::from_iter(chars.iter())
+                        .split('\n')
+                        .map(|l| l.to_owned())
+                        .collect::<Vec<String>>();
+                    let a = self.below(lines.len());
+                    let b = self.below(lines.len());
+                    match op {
+                        4 => lines.insert(a, lines[b].clone()),
+                        5 => {
+                            lines.remove(a);
+                        }
+                        _ => lines.swap(a, b),
+                    }
+                    chars = lines.join("\n").chars().collect();
+                }
+            }
+        }
+        String::from_iter(chars)
+    }
+}
+
+/// Run `parse` on `iterations` mutations of `base`, panicking with a replayable report if any
+/// of them makes the parser panic. Returning `Err` is always fine.
+pub fn check_parser<T, E: Display>(
+    name: &str,
+    base: &str,
+    seed: u64,
+    iterations: usize,
+    parse: impl Fn(&str) -> Result<T, E>,
+) {
+    let mut mutator = Mutator::new(seed);
+    for iteration in 0..iterations {
+        let input = mutator.mutate(base);
+        if let Err(e) = catch_unwind(AssertUnwindSafe(|| parse(&input).map(|_| ()))) {
+            let msg = e
+                .downcast_ref::<String>()
+                .map(|s| s.as_str())
+                .or_else(|| e.downcast_ref::<&str>().copied())
+                .unwrap_or("unknown panic payload");
+            panic!(
+                "`{}` panicked on iteration {} of seed {}: {}\ninput: {:?}",
+                name, iteration, seed, msg, input
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fuzz::{check_parser, Mutator};
+
+    #[test]
+    fn test_mutator_is_deterministic() {
+        let base = "move 1 from 2 to 1\nmove 3 from 1 to 3\n";
+        let mut m1 = Mutator::new(7);
+        let mut m2 = Mutator::new(7);
+        for _ in 0..100 {
+            assert_eq!(m1.mutate(base), m2.mutate(base));
+        }
+        assert_ne!(Mutator::new(7).mutate(base), Mutator::new(8).mutate(base));
+    }
+
+    #[test]
+    #[should_panic(expected = "`first_byte` panicked")]
+    fn test_check_parser_reports_panics() {
+        check_parser("first_byte", "", 1, 10, |s| {
+            Ok::<u8, String>(s.as_bytes()[0])
+        });
+    }
+}
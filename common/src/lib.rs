@@ -0,0 +1,5 @@
+pub mod checked;
+pub mod fuzz;
+pub mod parse;
+pub mod profile;
+pub mod rng;
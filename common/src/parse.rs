@@ -0,0 +1,54 @@
+//! Line-oriented parsing that either fails on the first bad line or skips and reports it.
+//!
+//! Readers take a [`ParseMode`] and return a [`Parsed`] value holding the result and every
+//! [`Reject`] they skipped. The default mode is [`ParseMode::Strict`], so a bad line is never
+//! dropped silently unless the caller asks for it.
+
+use std::fmt::Display;
+
+/// How a reader treats lines it cannot parse.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Fail on the first bad line, naming its line number.
+    #[default]
+    Strict,
+    /// Skip bad lines and record them as [`Reject`]s.
+    Lenient,
+}
+
+/// A line skipped in [`ParseMode::Lenient`]. `line` is 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reject {
+    pub line: usize,
+    pub content: String,
+    pub reason: String,
+}
+
+impl Display for Reject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {:?} {}", self.line, self.content, self.reason)
+    }
+}
+
+/// A result together with the lines that were skipped to compute it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parsed<T> {
+    pub value: T,
+    pub rejects: Vec<Reject>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::{ParseMode, Reject};
+
+    #[test]
+    fn test_reject() {
+        assert_eq!(ParseMode::Strict, ParseMode::default());
+        let reject = Reject {
+            line: 3,
+            content: "A Q".to_string(),
+            reason: "is not a round".to_string(),
+        };
+        assert_eq!("line 3: \"A Q\" is not a round", reject.to_string());
+    }
+}
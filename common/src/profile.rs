@@ -0,0 +1,125 @@
+//! Opt-in heap profiling.
+//!
+//! With the `profile` feature enabled this crate installs [`CountingAllocator`] as the global
+//! allocator of whatever binary links it, and [`measure`] reports the allocation count, bytes
+//! allocated and peak heap size of the wrapped closure on stderr. Without the feature
+//! [`measure`] simply runs the closure.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "profile")]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+static CURRENT_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Wraps the system allocator and keeps running totals of what passes through it.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn record_alloc(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+        let current = CURRENT_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(size: usize) {
+        CURRENT_BYTES.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::record_dealloc(layout.size());
+            Self::record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Heap usage of one measured section.
+///
+/// `peak_bytes` is the largest total heap size seen while the section ran, so it includes
+/// anything that was already allocated when the section started.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub allocations: usize,
+    pub allocated_bytes: usize,
+    pub peak_bytes: usize,
+}
+
+/// Run `f` and return its result together with the heap usage it caused.
+///
+/// All counters stay at zero unless the `profile` feature is enabled.
+pub fn measure_stats<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let outer_peak = PEAK_BYTES.swap(CURRENT_BYTES.load(Ordering::Relaxed), Ordering::Relaxed);
+    let value = f();
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed) - allocated_bytes,
+        peak_bytes: PEAK_BYTES.fetch_max(outer_peak, Ordering::Relaxed),
+    };
+    (value, stats)
+}
+
+/// Run `f`, printing its heap usage under `label` when the `profile` feature is enabled.
+pub fn measure<T>(label: &str, f: impl FnOnce() -> T) -> T {
+    if cfg!(feature = "profile") {
+        let (value, stats) = measure_stats(f);
+        eprintln!(
+            "[profile] {}: {} allocations, {} bytes allocated, {} bytes peak heap",
+            label, stats.allocations, stats.allocated_bytes, stats.peak_bytes
+        );
+        value
+    } else {
+        f()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::profile::measure_stats;
+
+    #[test]
+    fn test_measure_stats() {
+        let (v, stats) = measure_stats(|| vec![0u8; 4096]);
+        assert_eq!(4096, v.len());
+        if cfg!(feature = "profile") {
+            assert!(stats.allocations >= 1);
+            assert!(stats.allocated_bytes >= 4096);
+            assert!(stats.peak_bytes >= 4096);
+        } else {
+            assert_eq!(0, stats.allocations);
+        }
+    }
+}
@@ -0,0 +1,441 @@
+//! Redistributing food items between elves so their calorie totals are as even as possible.
+//!
+//! Items are never split, and every elf keeps a (possibly empty) pack, so this is
+//! multiway number partitioning into as many parts as there are elves. Inputs with at most
+//! [`EXACT_ITEM_LIMIT`] items are solved exactly by branch and bound. Larger inputs use two
+//! heuristics and keep whichever plan scores better:
+//!
+//! - LPT (longest processing time first): hand out items largest first, each to the elf
+//!   with the lightest pack so far. Its largest pack is within 4/3 of optimal.
+//! - Multiway Karmarkar-Karp: start with every item alone in an m-way partition, then
+//!   repeatedly merge the two partitions with the largest spread, pairing the heaviest
+//!   part of one with the lightest of the other.
+//!
+//! Every [`Plan`] reports the lower bound for its objective so the remaining gap is known
+//! even when the heuristic result is not provably optimal.
+
+use std::collections::{BinaryHeap, HashMap};
+
+use color_eyre::eyre::eyre;
+
+/// Inputs with at most this many items are solved exactly.
+pub const EXACT_ITEM_LIMIT: usize = 14;
+
+/// What the planner minimizes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Objective {
+    /// The largest calorie total of any elf.
+    MaxTotal,
+    /// The variance of the elves' calorie totals.
+    Variance,
+}
+
+/// How a plan was found.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Method {
+    Exact,
+    Lpt,
+    KarmarkarKarp,
+}
+
+/// Hand the item worth `calories` from elf `from` to elf `to`. Elves are 1-based.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Transfer {
+    pub calories: u64,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// A redistribution of the items and how good it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    pub method: Method,
+    pub objective: Objective,
+    /// Each elf's items after the transfers, indexed like the input.
+    pub packs: Vec<Vec<u64>>,
+    pub totals: Vec<u64>,
+    pub transfers: Vec<Transfer>,
+    pub max_total: u64,
+    /// No plan can have a smaller largest total: `max(ceil(sum / elves), largest item)`.
+    pub max_total_lower_bound: u64,
+    pub variance: f64,
+    /// The variance if the totals differed by at most one calorie.
+    pub variance_lower_bound: f64,
+}
+
+impl Plan {
+    /// How far the plan is from the lower bound of its objective.
+    pub fn gap(&self) -> f64 {
+        match self.objective {
+            Objective::MaxTotal => (self.max_total - self.max_total_lower_bound) as f64,
+            Objective::Variance => self.variance - self.variance_lower_bound,
+        }
+    }
+}
+
+/// Plan a redistribution of `elves`' items, choosing the method by input size.
+pub fn plan(elves: &[Vec<u64>], objective: Objective) -> color_eyre::Result<Plan> {
+    let num_items = elves.iter().map(|e| e.len()).sum::<usize>();
+    let method = if num_items <= EXACT_ITEM_LIMIT {
+        Method::Exact
+    } else {
+        let lpt = plan_with(elves, objective, Method::Lpt)?;
+        let kk = plan_with(elves, objective, Method::KarmarkarKarp)?;
+        return Ok(
+            if score(&kk.totals, objective) < score(&lpt.totals, objective) {
+                kk
+            } else {
+                lpt
+            },
+        );
+    };
+    plan_with(elves, objective, method)
+}
+
+/// Plan a redistribution of `elves`' items with a specific method. [`Method::Exact`] takes
+/// time exponential in the number of items.
+pub fn plan_with(
+    elves: &[Vec<u64>],
+    objective: Objective,
+    method: Method,
+) -> color_eyre::Result<Plan> {
+    let num_elves = elves.len();
+    if num_elves == 0 {
+        return Err(eyre!("no elves to balance"));
+    }
+    let items = elves.iter().flatten().copied().collect::<Vec<u64>>();
+    let sum = items
+        .iter()
+        .try_fold(0u64, |acc, &v| acc.checked_add(v))
+        .ok_or(eyre!("combined calorie total overflowed `u64`"))?;
+
+    let bins = match method {
+        Method::Exact => exact(&items, num_elves, objective),
+        Method::Lpt => lpt(&items, num_elves),
+        Method::KarmarkarKarp => karmarkar_karp(&items, num_elves),
+    };
+    let packs = assign_to_elves(elves, bins);
+    let totals = packs
+        .iter()
+        .map(|p| p.iter().sum::<u64>())
+        .collect::<Vec<u64>>();
+    let largest_item = items.iter().copied().max().unwrap_or(0);
+    let n = num_elves as u64;
+    let r = (sum % n) as f64;
+    Ok(Plan {
+        method,
+        objective,
+        transfers: transfers(elves, &packs),
+        max_total: totals.iter().copied().max().unwrap_or(0),
+        max_total_lower_bound: (sum / n + u64::from(sum % n != 0)).max(largest_item),
+        variance: variance(&totals),
+        variance_lower_bound: r * (n as f64 - r) / (n * n) as f64,
+        packs,
+        totals,
+    })
+}
+
+fn variance(totals: &[u64]) -> f64 {
+    let n = totals.len() as f64;
+    let mean = totals.iter().map(|&t| t as f64).sum::<f64>() / n;
+    totals
+        .iter()
+        .map(|&t| (t as f64 - mean).powi(2))
+        .sum::<f64>()
+        / n
+}
+
+/// A value that only grows as items are added and orders plans by `objective`. The sum of
+/// the totals is fixed, so minimizing the sum of squares minimizes the variance.
+fn score(totals: &[u64], objective: Objective) -> u128 {
+    match objective {
+        Objective::MaxTotal => totals.iter().copied().max().unwrap_or(0) as u128,
+        Objective::Variance => totals.iter().map(|&t| t as u128 * t as u128).sum(),
+    }
+}
+
+fn sorted_desc(items: &[u64]) -> Vec<usize> {
+    let mut order = (0..items.len()).collect::<Vec<usize>>();
+    order.sort_unstable_by(|&a, &b| items[b].cmp(&items[a]));
+    order
+}
+
+/// Bins of item indices.
+type Bins = Vec<Vec<usize>>;
+
+fn lpt(items: &[u64], num_bins: usize) -> Bins {
+    let mut bins = vec![Vec::new(); num_bins];
+    let mut loads = BinaryHeap::new();
+    for b in 0..num_bins {
+        loads.push(std::cmp::Reverse((0u64, b)));
+    }
+    for idx in sorted_desc(items) {
+        let std::cmp::Reverse((load, b)) = loads.pop().expect("at least one bin");
+        bins[b].push(idx);
+        loads.push(std::cmp::Reverse((load + items[idx], b)));
+    }
+    bins
+}
+
+fn karmarkar_karp(items: &[u64], num_bins: usize) -> Bins {
+    // Each partition holds `num_bins` parts sorted by load, heaviest first.
+    type Partition = Vec<(u64, Vec<usize>)>;
+    let spread = |p: &Partition| p[0].0 - p[p.len() - 1].0;
+    let mut partitions: Vec<Option<Partition>> = Vec::new();
+    let mut heap = BinaryHeap::new();
+    for (idx, &item) in items.iter().enumerate() {
+        let mut p = vec![(0, Vec::new()); num_bins];
+        p[0] = (item, vec![idx]);
+        heap.push((spread(&p), partitions.len()));
+        partitions.push(Some(p));
+    }
+    while heap.len() > 1 {
+        let (_, a) = heap.pop().expect("two partitions");
+        let (_, b) = heap.pop().expect("two partitions");
+        let a = partitions[a].take().expect("partition used once");
+        let b = partitions[b].take().expect("partition used once");
+        let mut merged = a
+            .into_iter()
+            .zip(b.into_iter().rev())
+            .map(|((la, mut ia), (lb, ib))| {
+                ia.extend(ib);
+                (la + lb, ia)
+            })
+            .collect::<Partition>();
+        merged.sort_unstable_by_key(|p| std::cmp::Reverse(p.0));
+        heap.push((spread(&merged), partitions.len()));
+        partitions.push(Some(merged));
+    }
+    match heap.pop() {
+        Some((_, last)) => partitions[last]
+            .take()
+            .expect("partition used once")
+            .into_iter()
+            .map(|(_, ids)| ids)
+            .collect(),
+        None => vec![Vec::new(); num_bins],
+    }
+}
+
+fn exact(items: &[u64], num_bins: usize, objective: Objective) -> Bins {
+    struct Search<'a> {
+        items: &'a [u64],
+        order: Vec<usize>,
+        objective: Objective,
+        loads: Vec<u64>,
+        bins: Bins,
+        best_score: u128,
+        best: Bins,
+    }
+
+    impl Search<'_> {
+        fn run(&mut self, depth: usize) {
+            if score(&self.loads, self.objective) >= self.best_score {
+                return;
+            }
+            if depth == self.order.len() {
+                self.best_score = score(&self.loads, self.objective);
+                self.best = self.bins.clone();
+                return;
+            }
+            let idx = self.order[depth];
+            let mut tried_empty = false;
+            for b in 0..self.loads.len() {
+                // Empty bins are interchangeable, so only try the first one.
+                if self.loads[b] == 0 && self.bins[b].is_empty() {
+                    if tried_empty {
+                        continue;
+                    }
+                    tried_empty = true;
+                }
+                self.loads[b] += self.items[idx];
+                self.bins[b].push(idx);
+                self.run(depth + 1);
+                self.bins[b].pop();
+                self.loads[b] -= self.items[idx];
+            }
+        }
+    }
+
+    // Start from the better heuristic so that most branches are pruned immediately.
+    let heuristic = [lpt(items, num_bins), karmarkar_karp(items, num_bins)]
+        .into_iter()
+        .min_by_key(|bins| score(&bin_loads(items, bins), objective))
+        .expect("two heuristics");
+    let mut search = Search {
+        items,
+        order: sorted_desc(items),
+        objective,
+        loads: vec![0; num_bins],
+        bins: vec![Vec::new(); num_bins],
+        best_score: score(&bin_loads(items, &heuristic), objective),
+        best: heuristic,
+    };
+    // The heuristic may already be optimal; allow the search to match it.
+    search.best_score += 1;
+    search.run(0);
+    search.best
+}
+
+fn bin_loads(items: &[u64], bins: &Bins) -> Vec<u64> {
+    bins.iter()
+        .map(|b| b.iter().map(|&i| items[i]).sum())
+        .collect()
+}
+
+/// Multiset overlap of two item lists.
+fn shared_items(a: &[u64], b: &[u64]) -> usize {
+    let mut counts = HashMap::new();
+    for &v in a {
+        *counts.entry(v).or_insert(0usize) += 1;
+    }
+    let mut shared = 0;
+    for v in b {
+        if let Some(c) = counts.get_mut(v) {
+            if *c > 0 {
+                *c -= 1;
+                shared += 1;
+            }
+        }
+    }
+    shared
+}
+
+/// Give each bin to the elf who already holds most of its items, greedily, so that as few
+/// items as possible have to change hands.
+fn assign_to_elves(elves: &[Vec<u64>], bins: Bins) -> Vec<Vec<u64>> {
+    let items = elves.iter().flatten().copied().collect::<Vec<u64>>();
+    let bins = bins
+        .into_iter()
+        .map(|b| b.into_iter().map(|i| items[i]).collect::<Vec<u64>>())
+        .collect::<Vec<Vec<u64>>>();
+    let mut pairs = Vec::with_capacity(elves.len() * bins.len());
+    for (e, elf) in elves.iter().enumerate() {
+        for (b, bin) in bins.iter().enumerate() {
+            pairs.push((shared_items(elf, bin), e, b));
+        }
+    }
+    pairs.sort_unstable_by(|x, y| y.0.cmp(&x.0).then(x.1.cmp(&y.1)).then(x.2.cmp(&y.2)));
+    let mut packs = vec![None; elves.len()];
+    let mut used = vec![false; bins.len()];
+    for (_, e, b) in pairs {
+        if packs[e].is_none() && !used[b] {
+            packs[e] = Some(bins[b].clone());
+            used[b] = true;
+        }
+    }
+    packs
+        .into_iter()
+        .map(|p| p.expect("every elf gets a bin"))
+        .collect()
+}
+
+fn transfers(before: &[Vec<u64>], after: &[Vec<u64>]) -> Vec<Transfer> {
+    // Items each elf gives away, by calorie value.
+    let mut givers: HashMap<u64, Vec<usize>> = HashMap::new();
+    let mut wanted = Vec::new();
+    for (e, (old, new)) in before.iter().zip(after.iter()).enumerate() {
+        let mut remaining = old.clone();
+        for &v in new {
+            match remaining.iter().position(|&r| r == v) {
+                Some(pos) => {
+                    remaining.swap_remove(pos);
+                }
+                None => wanted.push((v, e)),
+            }
+        }
+        for v in remaining {
+            givers.entry(v).or_default().push(e);
+        }
+    }
+    wanted
+        .into_iter()
+        .map(|(calories, to)| Transfer {
+            calories,
+            from: givers
+                .get_mut(&calories)
+                .and_then(|g| g.pop())
+                .expect("every wanted item is given away by someone")
+                + 1,
+            to: to + 1,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use common::rng::Rng;
+
+    use crate::balance::{plan, plan_with, Method, Objective};
+    use crate::{open_calorie_list, read_elf_items, ParseMode};
+
+    #[test]
+    fn test_exact_plan() {
+        let elves = read_elf_items(
+            open_calorie_list("src/test_input.txt").unwrap(),
+            ParseMode::Strict,
+        )
+        .unwrap()
+        .value;
+        let p = plan(&elves, Objective::MaxTotal).unwrap();
+        assert_eq!(Method::Exact, p.method);
+        assert_eq!(11_000, p.max_total);
+        assert_eq!(0.0, p.gap());
+        assert_eq!(vec![11_000; 5], p.totals);
+
+        let uneven = vec![vec![6, 5, 4], vec![3, 3], vec![2, 1]];
+        let p = plan(&uneven, Objective::Variance).unwrap();
+        assert_eq!(vec![8, 8, 8], {
+            let mut t = p.totals.clone();
+            t.sort();
+            t
+        });
+        let mut applied = uneven.clone();
+        for t in p.transfers.iter() {
+            let pos = applied[t.from - 1].iter().position(|&v| v == t.calories);
+            applied[t.from - 1].swap_remove(pos.unwrap());
+            applied[t.to - 1].push(t.calories);
+        }
+        for (a, b) in applied.iter_mut().zip(p.packs.clone().iter_mut()) {
+            a.sort();
+            b.sort();
+            assert_eq!(a, b);
+        }
+    }
+
+    #[test]
+    fn test_heuristic_plans() {
+        let elves = (1..=40u64)
+            .map(|e| (0..e % 4 + 1).map(|i| e * 37 % 101 + i * 13).collect())
+            .collect::<Vec<Vec<u64>>>();
+        let sum = elves.iter().flatten().sum::<u64>();
+        for method in [Method::Lpt, Method::KarmarkarKarp] {
+            let p = plan_with(&elves, Objective::MaxTotal, method).unwrap();
+            assert_eq!(sum, p.totals.iter().sum::<u64>());
+            assert!(p.max_total >= p.max_total_lower_bound);
+        }
+
+        // LPT is within 4/3 of the optimum; Karmarkar-Karp has no such guarantee.
+        let mut rng = Rng::new(34);
+        for _ in 0..40 {
+            let elves = (0..2 + rng.below(3))
+                .map(|_| {
+                    (0..1 + rng.below(4))
+                        .map(|_| 1 + rng.below(50) as u64)
+                        .collect()
+                })
+                .collect::<Vec<Vec<u64>>>();
+            let exact = plan_with(&elves, Objective::MaxTotal, Method::Exact)
+                .unwrap()
+                .max_total;
+            let lpt = plan_with(&elves, Objective::MaxTotal, Method::Lpt).unwrap();
+            assert!(exact <= lpt.max_total && lpt.max_total * 3 <= exact * 4);
+            let kk = plan_with(&elves, Objective::MaxTotal, Method::KarmarkarKarp).unwrap();
+            assert!(exact <= kk.max_total);
+        }
+        let p = plan(&elves, Objective::Variance).unwrap();
+        assert_ne!(Method::Exact, p.method);
+        assert!(p.variance >= p.variance_lower_bound);
+        assert!(plan(&[], Objective::MaxTotal).is_err());
+    }
+}
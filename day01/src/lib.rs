@@ -0,0 +1,405 @@
+//! Day 1: Calorie Counting.
+//!
+//! The input is one calorie count per line, with a blank line between elves. Elves are
+//! read one group at a time from any [`BufRead`], so only the current line and the
+//! running summaries are ever held in memory. Every reader goes through [`ElfGroups`],
+//! which either fails on the first bad line or skips and reports bad lines depending on
+//! its [`ParseMode`]. A skipped line does not end the current elf.
+
+pub mod balance;
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+
+use color_eyre::eyre::eyre;
+pub use common::parse::{ParseMode, Parsed, Reject};
+
+/// What a single elf is carrying. `elf` is the 1-based position of the elf in the input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ElfSummary {
+    pub elf: usize,
+    pub items: usize,
+    pub total: u64,
+    pub min_item: u64,
+    pub max_item: u64,
+}
+
+impl ElfSummary {
+    fn new(elf: usize, item: u64) -> Self {
+        Self {
+            elf,
+            items: 1,
+            total: item,
+            min_item: item,
+            max_item: item,
+        }
+    }
+
+    fn add(&mut self, item: u64) -> Option<()> {
+        self.total = self.total.checked_add(item)?;
+        self.items += 1;
+        self.min_item = self.min_item.min(item);
+        self.max_item = self.max_item.max(item);
+        Some(())
+    }
+
+    /// The mean calories per item.
+    pub fn mean(&self) -> f64 {
+        self.total as f64 / self.items as f64
+    }
+}
+
+/// Iterator over the elves in a calorie list, yielding one [`ElfSummary`] per group.
+///
+/// Blank lines separate elves; runs of blank lines do not produce empty elves.
+#[derive(Debug)]
+pub struct ElfGroups<R> {
+    reader: R,
+    mode: ParseMode,
+    line: String,
+    line_number: usize,
+    elves: usize,
+    rejects: Vec<Reject>,
+}
+
+impl<R: BufRead> ElfGroups<R> {
+    /// Read elves from `reader` one group at a time.
+    pub fn new(reader: R, mode: ParseMode) -> Self {
+        Self {
+            reader,
+            mode,
+            line: String::new(),
+            line_number: 0,
+            elves: 0,
+            rejects: Vec::new(),
+        }
+    }
+
+    /// The lines skipped so far. Always empty in [`ParseMode::Strict`].
+    pub fn rejects(&self) -> &[Reject] {
+        &self.rejects
+    }
+
+    /// Stop reading and return the lines skipped so far.
+    pub fn into_rejects(self) -> Vec<Reject> {
+        self.rejects
+    }
+
+    fn reject(&mut self, reason: String) -> color_eyre::Result<()> {
+        let reject = Reject {
+            line: self.line_number,
+            content: self.line.trim().to_owned(),
+            reason,
+        };
+        match self.mode {
+            ParseMode::Strict => Err(eyre!("{}", reject)),
+            ParseMode::Lenient => {
+                self.rejects.push(reject);
+                Ok(())
+            }
+        }
+    }
+
+    /// The next elf together with its accepted items, in input order.
+    pub fn next_with_items(&mut self) -> color_eyre::Result<Option<(ElfSummary, Vec<u64>)>> {
+        let mut items = Vec::new();
+        Ok(self
+            .next_group(|item| items.push(item))?
+            .map(|summary| (summary, items)))
+    }
+
+    fn next_group(
+        &mut self,
+        mut on_item: impl FnMut(u64),
+    ) -> color_eyre::Result<Option<ElfSummary>> {
+        let mut summary: Option<ElfSummary> = None;
+        loop {
+            self.line.clear();
+            if self.reader.read_line(&mut self.line)? == 0 {
+                return Ok(summary);
+            }
+            self.line_number += 1;
+            let line = self.line.trim();
+            if line.is_empty() {
+                if summary.is_some() {
+                    return Ok(summary);
+                }
+                continue;
+            }
+            let item = match line.parse::<u64>() {
+                Ok(item) => item,
+                Err(e) => {
+                    self.reject(format!("is not a calorie count: {}", e))?;
+                    continue;
+                }
+            };
+            match summary.as_mut() {
+                Some(s) => {
+                    if s.add(item).is_none() {
+                        let reason = format!("overflows the calorie total of elf {}", s.elf);
+                        self.reject(reason)?;
+                        continue;
+                    }
+                }
+                None => {
+                    self.elves += 1;
+                    summary = Some(ElfSummary::new(self.elves, item));
+                }
+            }
+            on_item(item);
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for ElfGroups<R> {
+    type Item = color_eyre::Result<ElfSummary>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_group(|_| ()).transpose()
+    }
+}
+
+/// Heap entry ordering elves by total, with the earlier elf winning ties.
+#[derive(Debug, PartialEq, Eq)]
+struct Ranked(ElfSummary);
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .total
+            .cmp(&other.0.total)
+            .then_with(|| other.0.elf.cmp(&self.0.elf))
+    }
+}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The `k` elves carrying the most calories, best first. Ties go to the earlier elf.
+///
+/// Uses a min-heap bounded to `k` entries, so memory does not grow with the input.
+pub fn top_k<R: BufRead>(
+    reader: R,
+    k: usize,
+    mode: ParseMode,
+) -> color_eyre::Result<Parsed<Vec<ElfSummary>>> {
+    let mut groups = ElfGroups::new(reader, mode);
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for summary in groups.by_ref() {
+        heap.push(Reverse(Ranked(summary?)));
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+    Ok(Parsed {
+        value: heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(Ranked(s))| s)
+            .collect(),
+        rejects: groups.into_rejects(),
+    })
+}
+
+/// The combined calories of `elves`.
+pub fn total_calories(elves: &[ElfSummary]) -> color_eyre::Result<u64> {
+    elves
+        .iter()
+        .try_fold(0u64, |acc, e| acc.checked_add(e.total))
+        .ok_or(eyre!("combined calorie total overflowed `u64`"))
+}
+
+/// Write a CSV header and then one row per elf as the groups are read, returning the
+/// skipped lines.
+pub fn write_summary_csv<R: BufRead, W: Write>(
+    reader: R,
+    mut writer: W,
+    mode: ParseMode,
+) -> color_eyre::Result<Vec<Reject>> {
+    writeln!(writer, "elf,items,total,mean,min_item,max_item")?;
+    let mut groups = ElfGroups::new(reader, mode);
+    for summary in groups.by_ref() {
+        let s = summary?;
+        writeln!(
+            writer,
+            "{},{},{},{:.2},{},{}",
+            s.elf,
+            s.items,
+            s.total,
+            s.mean(),
+            s.min_item,
+            s.max_item
+        )?;
+    }
+    Ok(groups.into_rejects())
+}
+
+/// Every elf's items, in input order. Unlike the other readers this holds the whole list.
+pub fn read_elf_items<R: BufRead>(
+    reader: R,
+    mode: ParseMode,
+) -> color_eyre::Result<Parsed<Vec<Vec<u64>>>> {
+    let mut groups = ElfGroups::new(reader, mode);
+    let mut elves = Vec::new();
+    while let Some((_, items)) = groups.next_with_items()? {
+        elves.push(items);
+    }
+    Ok(Parsed {
+        value: elves,
+        rejects: groups.into_rejects(),
+    })
+}
+
+/// Open `input` for streaming.
+pub fn open_calorie_list(input: &str) -> color_eyre::Result<BufReader<File>> {
+    Ok(BufReader::new(File::open(input)?))
+}
+
+/// Read `input` and return the largest calorie total carried by a single elf.
+pub fn find_elf_with_max_calories(input: &str, mode: ParseMode) -> color_eyre::Result<Parsed<u64>> {
+    max_calories(open_calorie_list(input)?, mode)
+}
+
+/// The largest calorie total carried by a single elf.
+pub fn max_calories<R: BufRead>(reader: R, mode: ParseMode) -> color_eyre::Result<Parsed<u64>> {
+    let Parsed { value, rejects } = top_k(reader, 1, mode)?;
+    let max = value
+        .first()
+        .map(|e| e.total)
+        .ok_or(eyre!("no summable calorie counts found"))?;
+    Ok(Parsed {
+        value: max,
+        rejects,
+    })
+}
+
+/// Read `input` and return the combined calories of the three best-stocked elves.
+pub fn find_top_three_elf_calories(
+    input: &str,
+    mode: ParseMode,
+) -> color_eyre::Result<Parsed<u64>> {
+    top_three_calories(open_calorie_list(input)?, mode)
+}
+
+/// The combined calories of the three best-stocked elves.
+pub fn top_three_calories<R: BufRead>(
+    reader: R,
+    mode: ParseMode,
+) -> color_eyre::Result<Parsed<u64>> {
+    let Parsed { value, rejects } = top_k(reader, 3, mode)?;
+    Ok(Parsed {
+        value: total_calories(&value)?,
+        rejects,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fuzz::check_parser;
+
+    use crate::{
+        find_elf_with_max_calories, find_top_three_elf_calories, max_calories, open_calorie_list,
+        top_k, top_three_calories, write_summary_csv, ParseMode, Reject,
+    };
+
+    #[test]
+    fn test_part_one() {
+        assert_eq!(
+            24_000,
+            find_elf_with_max_calories("src/test_input.txt", ParseMode::Strict)
+                .unwrap()
+                .value
+        );
+    }
+
+    #[test]
+    fn test_part_two() {
+        assert_eq!(
+            45_000,
+            find_top_three_elf_calories("src/test_input.txt", ParseMode::Strict)
+                .unwrap()
+                .value
+        );
+    }
+
+    #[test]
+    fn test_top_k() {
+        let list = open_calorie_list("src/test_input.txt").unwrap();
+        let top = top_k(list, 4, ParseMode::Strict).unwrap().value;
+        assert_eq!(
+            vec![(4, 24_000), (3, 11_000), (5, 10_000), (1, 6_000)],
+            top.iter().map(|e| (e.elf, e.total)).collect::<Vec<_>>()
+        );
+        let tied = top_k("5\n\n7\n\n5\n\n\n\n7\n".as_bytes(), 3, ParseMode::Strict)
+            .unwrap()
+            .value;
+        assert_eq!(
+            vec![2, 4, 1],
+            tied.iter().map(|e| e.elf).collect::<Vec<_>>()
+        );
+        assert!(top_k("".as_bytes(), 3, ParseMode::Strict)
+            .unwrap()
+            .value
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_modes() {
+        let data = "100\nabc\n200\n\n18446744073709551615\n1\n\n50\n";
+        let e = max_calories(data.as_bytes(), ParseMode::Strict).unwrap_err();
+        assert!(e
+            .to_string()
+            .starts_with("line 2: \"abc\" is not a calorie count"));
+
+        let lenient = max_calories(data.as_bytes(), ParseMode::Lenient).unwrap();
+        assert_eq!(u64::MAX, lenient.value);
+        assert_eq!(
+            vec![
+                Reject {
+                    line: 2,
+                    content: "abc".to_owned(),
+                    reason: "is not a calorie count: invalid digit found in string".to_owned()
+                },
+                Reject {
+                    line: 6,
+                    content: "1".to_owned(),
+                    reason: "overflows the calorie total of elf 2".to_owned()
+                },
+            ],
+            lenient.rejects
+        );
+        let top_three = top_three_calories(data.as_bytes(), ParseMode::Lenient);
+        assert!(top_three.is_err());
+    }
+
+    #[test]
+    fn test_summary_csv() {
+        let mut csv = Vec::new();
+        let list = open_calorie_list("src/test_input.txt").unwrap();
+        let rejects = write_summary_csv(list, &mut csv, ParseMode::Lenient).unwrap();
+        assert!(rejects.is_empty());
+        let csv = String::from_utf8(csv).unwrap();
+        let mut rows = csv.lines();
+        assert_eq!(Some("elf,items,total,mean,min_item,max_item"), rows.next());
+        assert_eq!(Some("1,3,6000,2000.00,1000,3000"), rows.next());
+        assert_eq!(Some("5,1,10000,10000.00,10000,10000"), rows.last());
+    }
+
+    #[test]
+    fn test_fuzz_parsers() {
+        let base = std::fs::read_to_string("src/test_input.txt").unwrap();
+        for mode in [ParseMode::Strict, ParseMode::Lenient] {
+            check_parser("max_calories", &base, 1, 2_000, |s| {
+                max_calories(s.as_bytes(), mode)
+            });
+            check_parser("top_three_calories", &base, 1, 2_000, |s| {
+                top_three_calories(s.as_bytes(), mode)
+            });
+        }
+    }
+}
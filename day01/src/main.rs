@@ -1,71 +1,73 @@
-use color_eyre::eyre::eyre;
-use itertools::Itertools;
+use common::profile::measure;
+use day1::balance::{self, Objective};
+use day1::{
+    find_elf_with_max_calories, find_top_three_elf_calories, open_calorie_list, read_elf_items,
+    write_summary_csv, ParseMode, Reject,
+};
 
-fn find_elf_with_max_calories(input: &str) -> color_eyre::Result<u64> {
-    let max = std::fs::read_to_string(input)?
-        .lines()
-        .map(|v| v.parse::<u64>().ok())
-        .batching(|it| {
-            let mut sum = None;
-            while let Some(Some(v)) = it.next() {
-                sum = Some(sum.unwrap_or(0) + v);
-            }
-            sum
-        })
-        .max()
-        .ok_or(eyre!("no summable calorie counts found"))?;
-
-    Ok(max)
-}
-
-fn find_top_three_elf_calories(input: &str) -> color_eyre::Result<u64> {
-    let mut top_three: [u64; 3] = [0; 3];
-    for group in std::fs::read_to_string(input)?
-        .replace("\r\n", "\n")
-        .split("\n\n")
-    {
-        let mut sum = 0;
-        for line in group.lines() {
-            let value = line.parse::<u64>()?;
-            sum += value;
-        }
-        if sum > top_three[0] {
-            top_three[0] = sum;
-        }
-        top_three.sort();
+fn report_rejects(rejects: &[Reject]) {
+    for reject in rejects {
+        eprintln!("skipped {}", reject);
     }
-    Ok(top_three.iter().sum())
 }
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
 
-    let max = find_elf_with_max_calories("src/input.txt")?;
-    println!("Part one: {max}");
-
-    let top_three = find_top_three_elf_calories("src/input.txt")?;
-    println!("Part two: {top_three}");
-
-    Ok(())
-}
+    let mode = if std::env::args().any(|a| a == "--lenient") {
+        ParseMode::Lenient
+    } else {
+        ParseMode::Strict
+    };
 
-#[cfg(test)]
-mod tests {
-    use crate::{find_elf_with_max_calories, find_top_three_elf_calories};
-
-    #[test]
-    fn test_part_one() {
-        assert_eq!(
-            24_000,
-            find_elf_with_max_calories("src/test_input.txt").unwrap()
-        );
+    if std::env::args().any(|a| a == "--csv") {
+        let list = open_calorie_list("src/input.txt")?;
+        report_rejects(&write_summary_csv(list, std::io::stdout().lock(), mode)?);
+        return Ok(());
     }
 
-    #[test]
-    fn test_part_two() {
-        assert_eq!(
-            45_000,
-            find_top_three_elf_calories("src/test_input.txt").unwrap()
+    if std::env::args().any(|a| a == "--balance") {
+        let objective = if std::env::args().any(|a| a == "--variance") {
+            Objective::Variance
+        } else {
+            Objective::MaxTotal
+        };
+        let elves = read_elf_items(open_calorie_list("src/input.txt")?, mode)?;
+        report_rejects(&elves.rejects);
+        let before = elves.value.iter().map(|e| e.iter().sum::<u64>()).max();
+        let plan = measure("balance", || balance::plan(&elves.value, objective))?;
+        println!("Method: {:?}", plan.method);
+        println!(
+            "Largest total: {} -> {} (lower bound {})",
+            before.unwrap_or(0),
+            plan.max_total,
+            plan.max_total_lower_bound
         );
+        println!(
+            "Variance: {:.2} (lower bound {:.2})",
+            plan.variance, plan.variance_lower_bound
+        );
+        println!("Gap: {:.2}", plan.gap());
+        println!("Transfers: {}", plan.transfers.len());
+        for t in plan.transfers.iter() {
+            println!(
+                "  {} calories from elf {} to elf {}",
+                t.calories, t.from, t.to
+            );
+        }
+        return Ok(());
     }
+
+    let max = measure("part one", || {
+        find_elf_with_max_calories("src/input.txt", mode)
+    })?;
+    report_rejects(&max.rejects);
+    println!("Part one: {}", max.value);
+
+    let top_three = measure("part two", || {
+        find_top_three_elf_calories("src/input.txt", mode)
+    })?;
+    println!("Part two: {}", top_three.value);
+
+    Ok(())
 }
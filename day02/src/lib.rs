@@ -0,0 +1,378 @@
+//! Day 2: Rock Paper Scissors.
+//!
+//! Each line of the strategy guide holds the opponent's move and a second column that is
+//! either the player's move (part one) or the desired round outcome (part two).
+//!
+//! Games are described by [`Rules`]: a list of moves and which move beats which. Rock
+//! Paper Scissors is the [`Rules::rps`] preset; [`Rules::cyclic`] builds any odd-sized
+//! balanced game such as [`Rules::rpsls`]. How the guide's symbols map onto moves and
+//! outcomes, and the points awarded, can be read from a rules file (see [`rules_file`]).
+
+use anyhow::{anyhow, Result};
+use common::checked;
+pub use common::parse::{ParseMode, Parsed, Reject};
+
+pub mod rules_file;
+pub mod sim;
+pub mod solver;
+
+use rules_file::RulesFile;
+
+const DAY: &str = "day02";
+
+/// Read `input` reading the second column as the player's move.
+pub fn read_guide_p1(input: &str, mode: ParseMode) -> Result<Parsed<Vec<Game>>> {
+    parse_guide_p1(&std::fs::read_to_string(input)?, mode)
+}
+
+/// Parse a strategy guide, reading the second column as the player's move.
+pub fn parse_guide_p1(data: &str, mode: ParseMode) -> Result<Parsed<Vec<Game>>> {
+    let standard = RulesFile::standard();
+    standard.interpretations[0].parse_guide(&standard.rules, data, mode)
+}
+
+/// Read `input` reading the second column as the desired outcome.
+pub fn read_guide_p2(input: &str, mode: ParseMode) -> Result<Parsed<Vec<Game>>> {
+    parse_guide_p2(&std::fs::read_to_string(input)?, mode)
+}
+
+/// Parse a strategy guide, reading the second column as the desired outcome.
+pub fn parse_guide_p2(data: &str, mode: ParseMode) -> Result<Parsed<Vec<Game>>> {
+    let standard = RulesFile::standard();
+    standard.interpretations[1].parse_guide(&standard.rules, data, mode)
+}
+
+/// The outcome the player is asked to reach in a round.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RoundStrategy {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl RoundStrategy {
+    /// The move that reaches this outcome against `opponent` under `rules`. When several
+    /// moves do, the one worth the most points is played.
+    pub fn play(&self, rules: &Rules, opponent: RpsMove) -> RpsMove {
+        rules
+            .moves()
+            .filter(|&m| rules.outcome(m, opponent) == *self)
+            .max_by_key(|&m| rules.shape_points(m))
+            .expect("every outcome is reachable against every move")
+    }
+}
+
+/// A move, identified by its index in the [`Rules`] it is played under.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RpsMove(pub u8);
+
+impl RpsMove {
+    /// Rock in [`Rules::rps`] and [`Rules::rpsls`].
+    pub const R: Self = Self(0);
+    /// Paper in [`Rules::rps`] and [`Rules::rpsls`].
+    pub const P: Self = Self(1);
+    /// Scissors in [`Rules::rps`] and [`Rules::rpsls`].
+    pub const S: Self = Self(2);
+}
+
+/// The moves of a game, which move beats which, and the points they score.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    names: Vec<String>,
+    /// `beats[a][b]` holds when move `a` beats move `b`.
+    beats: Vec<Vec<bool>>,
+    shape_points: Vec<u32>,
+    /// Points for losing, drawing and winning.
+    outcome_points: [u32; 3],
+}
+
+impl Rules {
+    /// A game with the moves `names` where move `a` beats move `b` if `beats(a, b)`.
+    /// Every pair of distinct moves must have exactly one winner, and every move must
+    /// beat and be beaten by some other move. Each move is worth one more point than its
+    /// index, and a loss, draw and win are worth 0, 3 and 6 points.
+    pub fn new<S: AsRef<str>>(names: &[S], beats: impl Fn(usize, usize) -> bool) -> Result<Self> {
+        let names = names.iter().map(|s| s.as_ref()).collect::<Vec<&str>>();
+        let n = names.len();
+        if !(3..=u8::MAX as usize).contains(&n) {
+            return Err(anyhow!("A game needs between 3 and 255 moves, got {n}"));
+        }
+        if let Some(b) = (1..n).find(|&b| names[..b].contains(&names[b])) {
+            return Err(anyhow!("Move `{}` is listed more than once", names[b]));
+        }
+        let beats = (0..n)
+            .map(|a| (0..n).map(|b| a != b && beats(a, b)).collect::<Vec<bool>>())
+            .collect::<Vec<Vec<bool>>>();
+        for a in 0..n {
+            for b in a + 1..n {
+                if beats[a][b] == beats[b][a] {
+                    return Err(anyhow!(
+                        "Exactly one of `{}` and `{}` must beat the other",
+                        names[a],
+                        names[b]
+                    ));
+                }
+            }
+            if !beats[a].contains(&true) || !(0..n).any(|b| beats[b][a]) {
+                return Err(anyhow!(
+                    "`{}` must beat some move and lose to some move",
+                    names[a]
+                ));
+            }
+        }
+        Ok(Self {
+            names: names.iter().map(|s| s.to_string()).collect(),
+            beats,
+            shape_points: (1..=n as u32).collect(),
+            outcome_points: [0, 3, 6],
+        })
+    }
+
+    /// The same game scored with `shape_points` for each move and `outcome_points` for a
+    /// loss, draw and win.
+    pub fn with_points(mut self, shape_points: Vec<u32>, outcome_points: [u32; 3]) -> Result<Self> {
+        if shape_points.len() != self.names.len() {
+            return Err(anyhow!(
+                "Expected points for {} moves, got {}",
+                self.names.len(),
+                shape_points.len()
+            ));
+        }
+        self.shape_points = shape_points;
+        self.outcome_points = outcome_points;
+        Ok(self)
+    }
+
+    /// The balanced game on an odd number of moves, where each move beats the moves an
+    /// odd number of places before it in `names`, wrapping around.
+    pub fn cyclic<S: AsRef<str>>(names: &[S]) -> Result<Self> {
+        if names.len().is_multiple_of(2) {
+            return Err(anyhow!("A cyclic game needs an odd number of moves"));
+        }
+        let n = names.len();
+        Self::new(names, |a, b| (a + n - b) % n % 2 == 1)
+    }
+
+    /// Rock Paper Scissors.
+    pub fn rps() -> Self {
+        Self::cyclic(&["Rock", "Paper", "Scissors"]).expect("valid preset")
+    }
+
+    /// Rock Paper Scissors Lizard Spock.
+    pub fn rpsls() -> Self {
+        Self::cyclic(&["Rock", "Paper", "Scissors", "Spock", "Lizard"]).expect("valid preset")
+    }
+
+    /// The number of moves in the game.
+    pub fn num_moves(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Every move of the game, in order.
+    pub fn moves(&self) -> impl Iterator<Item = RpsMove> {
+        (0..self.names.len() as u8).map(RpsMove)
+    }
+
+    /// The name of `m`.
+    pub fn name(&self, m: RpsMove) -> &str {
+        &self.names[m.0 as usize]
+    }
+
+    /// The move called `name`.
+    pub fn find(&self, name: &str) -> Option<RpsMove> {
+        self.moves().find(|&m| self.name(m) == name)
+    }
+
+    /// Whether `a` beats `b`.
+    pub fn beats(&self, a: RpsMove, b: RpsMove) -> bool {
+        self.beats[a.0 as usize][b.0 as usize]
+    }
+
+    /// The outcome for the player playing `player` against `opponent`.
+    pub fn outcome(&self, player: RpsMove, opponent: RpsMove) -> RoundStrategy {
+        if self.beats(player, opponent) {
+            RoundStrategy::Win
+        } else if self.beats(opponent, player) {
+            RoundStrategy::Lose
+        } else {
+            RoundStrategy::Draw
+        }
+    }
+
+    /// The points for playing `m`.
+    pub fn shape_points(&self, m: RpsMove) -> u32 {
+        self.shape_points[m.0 as usize]
+    }
+
+    /// The points for reaching `outcome`.
+    pub fn outcome_points(&self, outcome: RoundStrategy) -> u32 {
+        match outcome {
+            RoundStrategy::Lose => self.outcome_points[0],
+            RoundStrategy::Draw => self.outcome_points[1],
+            RoundStrategy::Win => self.outcome_points[2],
+        }
+    }
+}
+
+/// A single round of the tournament.
+#[derive(Debug, Copy, Clone)]
+pub struct Game {
+    pub player_move: RpsMove,
+    pub opponent_move: RpsMove,
+}
+
+/// The player's total score over all `games` played under `rules`.
+pub fn total_score(rules: &Rules, games: &[Game]) -> Result<u64> {
+    Ok(checked::sum(
+        DAY,
+        "total score",
+        games
+            .iter()
+            .map(|g| g.score(rules))
+            .collect::<Result<Vec<u64>>>()?,
+    )?)
+}
+
+impl Game {
+    /// The player's score for this round under `rules`: shape points plus outcome points.
+    pub fn score(&self, rules: &Rules) -> Result<u64> {
+        Ok(checked::add(
+            DAY,
+            "round score",
+            rules.shape_points(self.player_move) as u64,
+            rules.outcome_points(rules.outcome(self.player_move, self.opponent_move)) as u64,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fuzz::check_parser;
+
+    use crate::{
+        parse_guide_p1, parse_guide_p2, read_guide_p1, read_guide_p2, total_score, Game, ParseMode,
+        Reject, RoundStrategy, RpsMove, Rules,
+    };
+
+    #[test]
+    fn test_part_one() {
+        assert_eq!(
+            15,
+            read_guide_p1("src/test_input.txt", ParseMode::Strict)
+                .unwrap()
+                .value
+                .iter()
+                .map(|g| g.score(&Rules::rps()).unwrap())
+                .sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn test_part_two() {
+        assert_eq!(
+            12,
+            read_guide_p2("src/test_input.txt", ParseMode::Strict)
+                .unwrap()
+                .value
+                .iter()
+                .map(|g| g.score(&Rules::rps()).unwrap())
+                .sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn test_cyclic_rules() {
+        let rps = Rules::rps();
+        assert!(rps.beats(RpsMove::P, RpsMove::R));
+        assert!(rps.beats(RpsMove::R, RpsMove::S));
+        assert!(rps.beats(RpsMove::S, RpsMove::P));
+
+        let rpsls = Rules::rpsls();
+        let named = |name: &str| rpsls.find(name).unwrap();
+        for (a, b) in [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ] {
+            assert!(rpsls.beats(named(a), named(b)), "{a} beats {b}");
+            assert!(!rpsls.beats(named(b), named(a)), "{b} loses to {a}");
+        }
+        for m in rpsls.moves() {
+            for outcome in [RoundStrategy::Lose, RoundStrategy::Draw, RoundStrategy::Win] {
+                assert_eq!(outcome, rpsls.outcome(outcome.play(&rpsls, m), m));
+            }
+        }
+        let game = Game {
+            player_move: named("Lizard"),
+            opponent_move: named("Spock"),
+        };
+        assert_eq!(11, total_score(&rpsls, &[game]).unwrap());
+
+        assert!(Rules::cyclic(&["a", "b", "c", "d"]).is_err());
+        assert!(Rules::new(&["a", "b", "c"], |a, b| a > b).is_err());
+        assert!(Rules::cyclic(&["a", "b", "a"]).is_err());
+
+        let rules = Rules::rps()
+            .with_points(vec![u32::MAX; 3], [0, u32::MAX, u32::MAX])
+            .unwrap();
+        let game = Game {
+            player_move: RpsMove::R,
+            opponent_move: RpsMove::S,
+        };
+        assert_eq!(2 * u32::MAX as u64, game.score(&rules).unwrap());
+    }
+
+    #[test]
+    fn test_parse_modes() {
+        let data = "A Y\nB\n\nQ X\nC Z extra\nC W\nC Z\n";
+        let p1 = parse_guide_p1(data, ParseMode::Lenient).unwrap();
+        assert_eq!(2, p1.value.len());
+        assert_eq!(
+            vec![
+                (2, "has no second column"),
+                (4, "has unknown opponent symbol \"Q\""),
+                (5, "has more than two columns"),
+                (6, "has unknown player symbol \"W\""),
+            ],
+            p1.rejects
+                .iter()
+                .map(|r| (r.line, r.reason.as_str()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Reject {
+                line: 2,
+                content: "B".to_string(),
+                reason: "has no second column".to_string(),
+            },
+            parse_guide_p2(data, ParseMode::Lenient).unwrap().rejects[0]
+        );
+
+        let err = parse_guide_p2(data, ParseMode::Strict).unwrap_err();
+        assert_eq!("line 2: \"B\" has no second column", err.to_string());
+        assert!(parse_guide_p1("A Y\nB X\n", ParseMode::Strict)
+            .unwrap()
+            .rejects
+            .is_empty());
+    }
+
+    #[test]
+    fn test_fuzz_parsers() {
+        let base = std::fs::read_to_string("src/test_input.txt").unwrap();
+        for mode in [ParseMode::Strict, ParseMode::Lenient] {
+            check_parser("parse_guide_p1", &base, 2, 2_000, |s| {
+                parse_guide_p1(s, mode)
+            });
+            check_parser("parse_guide_p2", &base, 2, 2_000, |s| {
+                parse_guide_p2(s, mode)
+            });
+        }
+    }
+}
@@ -1,183 +1,108 @@
-use anyhow::Result;
-
-fn read_guide_p1(input: &str) -> Result<Vec<Game>> {
-    let mut game = Vec::new();
-    for line in std::fs::read_to_string(input)?.lines() {
-        let mut moves = line.split(' ');
-        let opponent = moves.next();
-        let player = moves.next();
-        match (opponent, player) {
-            (Some(o), Some(p)) => {
-                let opponent = RpsMove::try_from(o);
-                let player = RpsMove::try_from(p);
-                match (opponent, player) {
-                    (Ok(o), Ok(p)) => {
-                        game.push(Game {
-                            player_move: p,
-                            opponent_move: o,
-                        });
-                    }
-                    (_, _) => continue,
-                }
-            }
-            (_, _) => continue,
-        }
-    }
-    Ok(game)
-}
-
-fn read_guide_p2(input: &str) -> Result<Vec<Game>> {
-    let mut game = Vec::new();
-    for line in std::fs::read_to_string(input)?.lines() {
-        let mut moves = line.split(' ');
-        let opponent = moves.next();
-        let player_strat = moves.next();
-        match (opponent, player_strat) {
-            (Some(o), Some(p)) => {
-                let opponent = RpsMove::try_from(o);
-                let player_strat = RoundStrategy::try_from(p);
-                match (opponent, player_strat) {
-                    (Ok(o), Ok(p)) => {
-                        game.push(Game {
-                            player_move: p.play(&o),
-                            opponent_move: o,
-                        });
-                    }
-                    (_, _) => continue,
-                }
-            }
-            (_, _) => continue,
-        }
-    }
-    Ok(game)
-}
-
-#[derive(Debug, Copy, Clone)]
-enum RoundStrategy {
-    Lose,
-    Draw,
-    Win,
-}
-
-impl TryFrom<&str> for RoundStrategy {
-    type Error = &'static str;
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "X" => Ok(Self::Lose),
-            "Y" => Ok(Self::Draw),
-            "Z" => Ok(Self::Win),
-            _ => Err("Could not parse round strategy"),
-        }
+use anyhow::{anyhow, Result};
+use common::profile::measure;
+use day2::rules_file::{comparison_table, RulesFile};
+use day2::sim::{summary_table, tournament, Frequency, Guide, Markov, Strategy, Uniform};
+use day2::solver::{solve, Constraints};
+use day2::{read_guide_p1, read_guide_p2, total_score, ParseMode, Reject, Rules};
+
+fn report_rejects(rejects: &[Reject]) {
+    for reject in rejects {
+        eprintln!("skipped {}", reject);
     }
 }
 
-impl RoundStrategy {
-    fn play(&self, opponent: &RpsMove) -> RpsMove {
-        match self {
-            Self::Lose => opponent.to_lose(),
-            Self::Draw => opponent.to_draw(),
-            Self::Win => opponent.to_win(),
-        }
+/// The value following `flag` in `args`, if the flag is present.
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Result<Option<&'a str>> {
+    match args.iter().position(|a| a == flag) {
+        Some(i) => Ok(Some(
+            args.get(i + 1)
+                .ok_or_else(|| anyhow!("`{}` expects a value", flag))?,
+        )),
+        None => Ok(None),
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-#[repr(u8)]
-enum RpsMove {
-    R = 1,
-    P = 2,
-    S = 3,
-}
-
-impl TryFrom<&str> for RpsMove {
-    type Error = &'static str;
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "A" | "X" => Ok(Self::R),
-            "B" | "Y" => Ok(Self::P),
-            "C" | "Z" => Ok(Self::S),
-            _ => Err("Could not parse RPS move"),
-        }
+fn main() -> Result<()> {
+    let args = std::env::args().collect::<Vec<String>>();
+    let mode = if args.iter().any(|a| a == "--strict") {
+        ParseMode::Strict
+    } else {
+        ParseMode::Lenient
+    };
+    let rules_path = flag_value(&args, "--rules")?;
+    if rules_path.is_some() || args.iter().any(|a| a == "--compare") {
+        let rules_file = match rules_path {
+            Some(path) => RulesFile::read(path)?,
+            None => RulesFile::standard(),
+        };
+        let data = std::fs::read_to_string("src/input.txt")?;
+        let evaluations = measure("compare", || rules_file.compare(&data, mode))?;
+        print!("{}", comparison_table(&evaluations));
+        return Ok(());
     }
-}
 
-impl RpsMove {
-    fn to_win(self) -> Self {
-        match self {
-            Self::R => Self::P,
-            Self::P => Self::S,
-            Self::S => Self::R,
-        }
-    }
-    fn to_draw(self) -> Self {
-        self
-    }
-    fn to_lose(self) -> Self {
-        match self {
-            Self::R => Self::S,
-            Self::P => Self::R,
-            Self::S => Self::P,
+    let constraints = Constraints {
+        max_per_shape: flag_value(&args, "--max-per-shape")?
+            .map(str::parse)
+            .transpose()?,
+        max_losses: flag_value(&args, "--max-losses")?
+            .map(str::parse)
+            .transpose()?,
+        no_repeat: args.iter().any(|a| a == "--no-repeat"),
+    };
+    if constraints != Constraints::default() {
+        let rules = Rules::rps();
+        let guide = read_guide_p1("src/input.txt", mode)?;
+        report_rejects(&guide.rejects);
+        let opponent = guide
+            .value
+            .iter()
+            .map(|g| g.opponent_move)
+            .collect::<Vec<_>>();
+        let solution = measure("solve", || solve(&rules, &opponent, constraints))?;
+        println!("Optimal score: {}", solution.score);
+        for m in rules.moves() {
+            let uses = solution.moves.iter().filter(|&&p| p == m).count();
+            println!("  {}: {}", rules.name(m), uses);
         }
+        return Ok(());
     }
-}
-
-#[derive(Debug, Copy, Clone)]
-struct Game {
-    player_move: RpsMove,
-    opponent_move: RpsMove,
-}
 
-impl Game {
-    fn score(&self) -> u8 {
-        match (self.opponent_move, self.player_move) {
-            (RpsMove::R, p @ RpsMove::P) => 6 + p as u8,
-            (RpsMove::R, p @ RpsMove::R) => 3 + p as u8,
-            (RpsMove::P, p @ RpsMove::S) => 6 + p as u8,
-            (RpsMove::P, p @ RpsMove::P) => 3 + p as u8,
-            (RpsMove::S, p @ RpsMove::R) => 6 + p as u8,
-            (RpsMove::S, p @ RpsMove::S) => 3 + p as u8,
-            (_, p) => p as u8,
+    if args.iter().any(|a| a == "--simulate") {
+        let rules = Rules::rps();
+        let guide = read_guide_p1("src/input.txt", mode)?;
+        report_rejects(&guide.rejects);
+        let opponent = guide
+            .value
+            .iter()
+            .map(|g| g.opponent_move)
+            .collect::<Vec<_>>();
+        let strategies: Vec<Box<dyn Strategy>> = vec![
+            Box::new(Uniform),
+            Box::new(Frequency::default()),
+            Box::new(Markov::default()),
+            Box::new(Guide::new(&guide.value)),
+        ];
+        let mut summaries = Vec::new();
+        for mut strategy in strategies {
+            summaries.push(measure("simulate", || {
+                tournament(&rules, &opponent, strategy.as_mut(), 0..100)
+            })?);
         }
+        print!("{}", summary_table(&summaries));
+        return Ok(());
     }
-}
 
-fn main() -> Result<()> {
-    let guide_p1 = read_guide_p1("src/input.txt").unwrap();
-    let total_score_p1: u64 = guide_p1.iter().map(|g| g.score() as u64).sum();
+    let rules = Rules::rps();
+    let guide_p1 = measure("parse part one", || read_guide_p1("src/input.txt", mode))?;
+    report_rejects(&guide_p1.rejects);
+    let total_score_p1 = measure("part one", || total_score(&rules, &guide_p1.value))?;
     println!("Part one: {total_score_p1}");
 
-    let guide_p2 = read_guide_p2("src/input.txt").unwrap();
-    let total_score_p2: u64 = guide_p2.iter().map(|g| g.score() as u64).sum();
+    let guide_p2 = measure("parse part two", || read_guide_p2("src/input.txt", mode))?;
+    report_rejects(&guide_p2.rejects);
+    let total_score_p2 = measure("part two", || total_score(&rules, &guide_p2.value))?;
     println!("Part two: {total_score_p2}");
 
     Ok(())
 }
-
-#[cfg(test)]
-mod tests {
-    use crate::{read_guide_p1, read_guide_p2};
-
-    #[test]
-    fn test_part_one() {
-        assert_eq!(
-            15,
-            read_guide_p1("src/test_input.txt")
-                .unwrap()
-                .iter()
-                .map(|g| g.score() as u64)
-                .sum::<u64>()
-        );
-    }
-
-    #[test]
-    fn test_part_two() {
-        assert_eq!(
-            12,
-            read_guide_p2("src/test_input.txt")
-                .unwrap()
-                .iter()
-                .map(|g| g.score() as u64)
-                .sum::<u64>()
-        );
-    }
-}
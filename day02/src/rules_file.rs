@@ -0,0 +1,348 @@
+//! Rules files: the moves of a game, its points, and how to read a strategy guide.
+//!
+//! A rules file is TOML. `moves` lists the move names and `beats` optionally lists
+//! `[winner, loser]` pairs; without it the game is [`Rules::cyclic`]. `[points]` sets
+//! `shapes` (one per move) and the `lose`, `draw` and `win` points. Each
+//! `[[interpretations]]` entry names a way to read the guide: an `opponent` symbol table,
+//! whether the second `column` is a `"move"` or an `"outcome"`, and a `player` symbol
+//! table mapping to move names or to `"lose"`, `"draw"` and `"win"`. `rules.toml` in
+//! this crate holds the puzzle's rules and both parts' interpretations.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+use crate::{total_score, Game, ParseMode, Parsed, Reject, RoundStrategy, RpsMove, Rules};
+
+const STANDARD: &str = include_str!("../rules.toml");
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRulesFile {
+    moves: Vec<String>,
+    beats: Option<Vec<(String, String)>>,
+    points: Option<RawPoints>,
+    interpretations: Vec<RawInterpretation>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawPoints {
+    shapes: Option<Vec<u32>>,
+    #[serde(default)]
+    lose: u32,
+    #[serde(default = "default_draw")]
+    draw: u32,
+    #[serde(default = "default_win")]
+    win: u32,
+}
+
+fn default_draw() -> u32 {
+    3
+}
+
+fn default_win() -> u32 {
+    6
+}
+
+#[derive(Debug, Copy, Clone, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ColumnKind {
+    Move,
+    Outcome,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawInterpretation {
+    name: String,
+    opponent: BTreeMap<String, String>,
+    column: ColumnKind,
+    player: BTreeMap<String, String>,
+}
+
+/// What the guide's second column means.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    /// The player's move.
+    Move(HashMap<String, RpsMove>),
+    /// The outcome the player should reach.
+    Outcome(HashMap<String, RoundStrategy>),
+}
+
+/// One way of reading a strategy guide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interpretation {
+    pub name: String,
+    pub opponent: HashMap<String, RpsMove>,
+    pub player: Column,
+}
+
+impl Interpretation {
+    /// Parse a strategy guide. Empty lines are ignored; other lines that are not two
+    /// space-separated symbols from the tables are handled according to `mode`.
+    pub fn parse_guide(
+        &self,
+        rules: &Rules,
+        data: &str,
+        mode: ParseMode,
+    ) -> Result<Parsed<Vec<Game>>> {
+        let mut games = Vec::new();
+        let mut rejects = Vec::new();
+        for (idx, line) in data.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            match self.round(rules, line) {
+                Ok(game) => games.push(game),
+                Err(reason) => {
+                    let reject = Reject {
+                        line: idx + 1,
+                        content: line.to_string(),
+                        reason,
+                    };
+                    match mode {
+                        ParseMode::Strict => return Err(anyhow!("{}", reject)),
+                        ParseMode::Lenient => rejects.push(reject),
+                    }
+                }
+            }
+        }
+        Ok(Parsed {
+            value: games,
+            rejects,
+        })
+    }
+
+    fn round(&self, rules: &Rules, line: &str) -> Result<Game, String> {
+        let (opponent, player) = match line.split(' ').collect::<Vec<&str>>()[..] {
+            [o, p] => (o, p),
+            [_] => return Err("has no second column".to_string()),
+            _ => return Err("has more than two columns".to_string()),
+        };
+        let opponent_move = *self
+            .opponent
+            .get(opponent)
+            .ok_or_else(|| format!("has unknown opponent symbol {:?}", opponent))?;
+        let unknown = || format!("has unknown player symbol {:?}", player);
+        let player_move = match &self.player {
+            Column::Move(table) => *table.get(player).ok_or_else(unknown)?,
+            Column::Outcome(table) => table
+                .get(player)
+                .ok_or_else(unknown)?
+                .play(rules, opponent_move),
+        };
+        Ok(Game {
+            player_move,
+            opponent_move,
+        })
+    }
+}
+
+/// A game's rules and the interpretations of the guide to evaluate under them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RulesFile {
+    pub rules: Rules,
+    pub interpretations: Vec<Interpretation>,
+}
+
+impl RulesFile {
+    /// The puzzle's rules, with the part one and part two interpretations in that order.
+    pub fn standard() -> Self {
+        static PARSED: OnceLock<RulesFile> = OnceLock::new();
+        PARSED
+            .get_or_init(|| Self::from_toml(STANDARD).expect("valid standard rules"))
+            .clone()
+    }
+
+    /// Read the rules file at `path`.
+    pub fn read(path: &str) -> Result<Self> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+            .map_err(|e| anyhow!("Invalid rules file {}: {}", path, e))
+    }
+
+    /// Parse a rules file.
+    pub fn from_toml(data: &str) -> Result<Self> {
+        let raw = toml::from_str::<RawRulesFile>(data)?;
+        let index = |name: &str| {
+            raw.moves
+                .iter()
+                .position(|m| m == name)
+                .ok_or_else(|| anyhow!("Unknown move `{}`", name))
+        };
+        let rules = match &raw.beats {
+            Some(pairs) => {
+                let mut beats = vec![vec![false; raw.moves.len()]; raw.moves.len()];
+                for (winner, loser) in pairs {
+                    beats[index(winner)?][index(loser)?] = true;
+                }
+                Rules::new(&raw.moves, |a, b| beats[a][b])?
+            }
+            None => Rules::cyclic(&raw.moves)?,
+        };
+        let rules = match raw.points {
+            Some(points) => {
+                let shapes = points
+                    .shapes
+                    .unwrap_or_else(|| (1..=raw.moves.len() as u32).collect());
+                rules.with_points(shapes, [points.lose, points.draw, points.win])?
+            }
+            None => rules,
+        };
+
+        let moves = |table: BTreeMap<String, String>| {
+            table
+                .into_iter()
+                .map(|(symbol, name)| Ok((symbol, RpsMove(index(&name)? as u8))))
+                .collect::<Result<HashMap<String, RpsMove>>>()
+        };
+        let mut interpretations = Vec::new();
+        for i in raw.interpretations {
+            let player = match i.column {
+                ColumnKind::Move => Column::Move(moves(i.player)?),
+                ColumnKind::Outcome => Column::Outcome(
+                    i.player
+                        .into_iter()
+                        .map(|(symbol, outcome)| Ok((symbol, parse_outcome(&outcome)?)))
+                        .collect::<Result<HashMap<String, RoundStrategy>>>()?,
+                ),
+            };
+            interpretations.push(Interpretation {
+                name: i.name,
+                opponent: moves(i.opponent)?,
+                player,
+            });
+        }
+        Ok(Self {
+            rules,
+            interpretations,
+        })
+    }
+
+    /// Evaluate the guide in `data` under every interpretation.
+    pub fn compare(&self, data: &str, mode: ParseMode) -> Result<Vec<Evaluation>> {
+        let mut evaluations = Vec::new();
+        for interpretation in self.interpretations.iter() {
+            let Parsed {
+                value: games,
+                rejects,
+            } = interpretation.parse_guide(&self.rules, data, mode)?;
+            let count = |outcome| {
+                games
+                    .iter()
+                    .filter(|g| self.rules.outcome(g.player_move, g.opponent_move) == outcome)
+                    .count()
+            };
+            evaluations.push(Evaluation {
+                name: interpretation.name.clone(),
+                rounds: games.len(),
+                skipped: rejects.len(),
+                wins: count(RoundStrategy::Win),
+                draws: count(RoundStrategy::Draw),
+                losses: count(RoundStrategy::Lose),
+                score: total_score(&self.rules, &games)?,
+            });
+        }
+        Ok(evaluations)
+    }
+}
+
+fn parse_outcome(outcome: &str) -> Result<RoundStrategy> {
+    match outcome {
+        "lose" => Ok(RoundStrategy::Lose),
+        "draw" => Ok(RoundStrategy::Draw),
+        "win" => Ok(RoundStrategy::Win),
+        o => Err(anyhow!(
+            "Unknown outcome `{}`, expected `lose`, `draw` or `win`",
+            o
+        )),
+    }
+}
+
+/// The result of playing a guide under one interpretation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evaluation {
+    pub name: String,
+    pub rounds: usize,
+    pub skipped: usize,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    pub score: u64,
+}
+
+/// The evaluations as an aligned text table, one row per interpretation.
+pub fn comparison_table(evaluations: &[Evaluation]) -> String {
+    let width = evaluations
+        .iter()
+        .map(|e| e.name.len())
+        .chain(["interpretation".len()])
+        .max()
+        .unwrap_or(0);
+    let mut s = format!(
+        "{:<width$} {:>7} {:>7} {:>7} {:>7} {:>7} {:>9}\n",
+        "interpretation", "rounds", "skipped", "wins", "draws", "losses", "score"
+    );
+    for e in evaluations {
+        s.push_str(&format!(
+            "{:<width$} {:>7} {:>7} {:>7} {:>7} {:>7} {:>9}\n",
+            e.name, e.rounds, e.skipped, e.wins, e.draws, e.losses, e.score
+        ));
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rules_file::{comparison_table, RulesFile};
+    use crate::ParseMode;
+
+    #[test]
+    fn test_compare() {
+        let data = std::fs::read_to_string("src/test_input.txt").unwrap();
+        let standard = RulesFile::standard();
+        let evaluations = standard.compare(&data, ParseMode::Strict).unwrap();
+        assert_eq!(
+            vec![(15, 1, 1, 1), (12, 1, 1, 1)],
+            evaluations
+                .iter()
+                .map(|e| (e.score, e.wins, e.draws, e.losses))
+                .collect::<Vec<_>>()
+        );
+        let table = comparison_table(&evaluations);
+        assert_eq!(3, table.lines().count());
+        assert!(table.lines().nth(1).unwrap().starts_with("part one"));
+
+        let custom = RulesFile::from_toml(
+            r#"
+            moves = ["Rock", "Paper", "Scissors", "Spock", "Lizard"]
+            [points]
+            shapes = [0, 0, 0, 0, 0]
+            win = 1
+            draw = 0
+            [[interpretations]]
+            name = "swapped"
+            opponent = { A = "Paper", B = "Rock", C = "Scissors" }
+            column = "outcome"
+            player = { X = "win", Y = "lose", Z = "draw" }
+            "#,
+        )
+        .unwrap();
+        let evaluations = custom.compare(&data, ParseMode::Lenient).unwrap();
+        assert_eq!(
+            (3, 1, 1),
+            (
+                evaluations[0].rounds,
+                evaluations[0].wins,
+                evaluations[0].score
+            )
+        );
+
+        assert!(RulesFile::from_toml(
+            "moves = [\"a\", \"b\", \"c\"]\ninterpretations = []\nbeats = [[\"a\", \"d\"]]"
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,317 @@
+//! Playing strategies against the opponent column of a guide.
+//!
+//! A [`Strategy`] picks the player's move each round and is shown the opponent's move
+//! afterwards. [`tournament`] plays the same opponent sequence once per seed and reports
+//! the mean score and outcome counts with 95% confidence intervals. Randomness comes from
+//! a seeded [`Rng`], so a seed always replays the same match.
+
+use anyhow::{anyhow, Result};
+use common::rng::Rng;
+
+use crate::{total_score, Game, RoundStrategy, RpsMove, Rules};
+
+/// A way of choosing the player's moves.
+pub trait Strategy {
+    /// A short name for reports.
+    fn name(&self) -> String;
+    /// Forget everything observed, before a new match.
+    fn reset(&mut self);
+    /// The player's move for round `round` (0-based).
+    fn choose(&mut self, rules: &Rules, round: usize, rng: &mut Rng) -> RpsMove;
+    /// Record the opponent's move for the round just played.
+    fn observe(&mut self, opponent: RpsMove);
+}
+
+fn random_move(rules: &Rules, rng: &mut Rng) -> RpsMove {
+    RpsMove(rng.below(rules.num_moves()) as u8)
+}
+
+/// Plays uniformly at random.
+#[derive(Debug, Clone, Default)]
+pub struct Uniform;
+
+impl Strategy for Uniform {
+    fn name(&self) -> String {
+        "uniform".to_string()
+    }
+    fn reset(&mut self) {}
+    fn choose(&mut self, rules: &Rules, _round: usize, rng: &mut Rng) -> RpsMove {
+        random_move(rules, rng)
+    }
+    fn observe(&mut self, _opponent: RpsMove) {}
+}
+
+/// Beats the opponent's most frequent move so far.
+#[derive(Debug, Clone, Default)]
+pub struct Frequency {
+    counts: Vec<usize>,
+}
+
+impl Strategy for Frequency {
+    fn name(&self) -> String {
+        "frequency".to_string()
+    }
+    fn reset(&mut self) {
+        self.counts.clear();
+    }
+    fn choose(&mut self, rules: &Rules, _round: usize, rng: &mut Rng) -> RpsMove {
+        match most_frequent(&self.counts) {
+            Some(predicted) => RoundStrategy::Win.play(rules, predicted),
+            None => random_move(rules, rng),
+        }
+    }
+    fn observe(&mut self, opponent: RpsMove) {
+        let idx = opponent.0 as usize;
+        if self.counts.len() <= idx {
+            self.counts.resize(idx + 1, 0);
+        }
+        self.counts[idx] += 1;
+    }
+}
+
+/// The move counted most often, preferring the earliest on ties.
+fn most_frequent(counts: &[usize]) -> Option<RpsMove> {
+    counts
+        .iter()
+        .enumerate()
+        .filter(|(_, &c)| c > 0)
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(&a.0)))
+        .map(|(m, _)| RpsMove(m as u8))
+}
+
+/// Predicts the opponent's next move from their last one with first-order transition
+/// counts, and beats it.
+#[derive(Debug, Clone, Default)]
+pub struct Markov {
+    last: Option<RpsMove>,
+    /// `transitions[a][b]` counts how often the opponent followed `a` with `b`.
+    transitions: Vec<Vec<usize>>,
+}
+
+impl Strategy for Markov {
+    fn name(&self) -> String {
+        "markov".to_string()
+    }
+    fn reset(&mut self) {
+        self.last = None;
+        self.transitions.clear();
+    }
+    fn choose(&mut self, rules: &Rules, _round: usize, rng: &mut Rng) -> RpsMove {
+        let predicted = self
+            .last
+            .and_then(|last| self.transitions.get(last.0 as usize))
+            .and_then(|row| most_frequent(row));
+        match predicted {
+            Some(predicted) => RoundStrategy::Win.play(rules, predicted),
+            None => random_move(rules, rng),
+        }
+    }
+    fn observe(&mut self, opponent: RpsMove) {
+        if let Some(last) = self.last {
+            let (from, to) = (last.0 as usize, opponent.0 as usize);
+            let size = self.transitions.len().max(from + 1).max(to + 1);
+            self.transitions.resize(size, Vec::new());
+            for row in self.transitions.iter_mut() {
+                row.resize(size, 0);
+            }
+            self.transitions[from][to] += 1;
+        }
+        self.last = Some(opponent);
+    }
+}
+
+/// Plays the guide's own moves in order, or the first move once they run out.
+#[derive(Debug, Clone)]
+pub struct Guide {
+    moves: Vec<RpsMove>,
+}
+
+impl Guide {
+    /// Play the player moves of `games`.
+    pub fn new(games: &[Game]) -> Self {
+        Self {
+            moves: games.iter().map(|g| g.player_move).collect(),
+        }
+    }
+}
+
+impl Strategy for Guide {
+    fn name(&self) -> String {
+        "guide".to_string()
+    }
+    fn reset(&mut self) {}
+    fn choose(&mut self, _rules: &Rules, round: usize, _rng: &mut Rng) -> RpsMove {
+        self.moves.get(round).copied().unwrap_or(RpsMove(0))
+    }
+    fn observe(&mut self, _opponent: RpsMove) {}
+}
+
+/// The result of one match.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MatchResult {
+    pub score: u64,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+/// Play `strategy` against the `opponent` moves with randomness from `seed`.
+pub fn play_match(
+    rules: &Rules,
+    opponent: &[RpsMove],
+    strategy: &mut dyn Strategy,
+    seed: u64,
+) -> Result<MatchResult> {
+    let mut rng = Rng::new(seed);
+    strategy.reset();
+    let mut games = Vec::with_capacity(opponent.len());
+    for (round, &opponent_move) in opponent.iter().enumerate() {
+        let player_move = strategy.choose(rules, round, &mut rng);
+        strategy.observe(opponent_move);
+        games.push(Game {
+            player_move,
+            opponent_move,
+        });
+    }
+    let count = |outcome| {
+        games
+            .iter()
+            .filter(|g| rules.outcome(g.player_move, g.opponent_move) == outcome)
+            .count()
+    };
+    Ok(MatchResult {
+        score: total_score(rules, &games)?,
+        wins: count(RoundStrategy::Win),
+        draws: count(RoundStrategy::Draw),
+        losses: count(RoundStrategy::Lose),
+    })
+}
+
+/// A sample mean with its 95% confidence interval, using the normal approximation.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Estimate {
+    fn from_samples(samples: impl Iterator<Item = f64> + Clone) -> Result<Self> {
+        let n = samples.clone().count() as f64;
+        if n == 0.0 {
+            return Err(anyhow!("Can't estimate from zero samples"));
+        }
+        let mean = samples.clone().sum::<f64>() / n;
+        let var = if n > 1.0 {
+            samples.map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        let half_width = 1.96 * (var / n).sqrt();
+        Ok(Self {
+            mean,
+            low: mean - half_width,
+            high: mean + half_width,
+        })
+    }
+}
+
+/// A strategy's results over many seeds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub strategy: String,
+    pub matches: usize,
+    pub score: Estimate,
+    pub wins: Estimate,
+    pub draws: Estimate,
+    pub losses: Estimate,
+}
+
+/// Play one match per seed in `seeds` and summarize the results.
+pub fn tournament(
+    rules: &Rules,
+    opponent: &[RpsMove],
+    strategy: &mut dyn Strategy,
+    seeds: std::ops::Range<u64>,
+) -> Result<Summary> {
+    let mut results = Vec::new();
+    for seed in seeds {
+        results.push(play_match(rules, opponent, strategy, seed)?);
+    }
+    let estimate = |f: fn(&MatchResult) -> f64| Estimate::from_samples(results.iter().map(f));
+    Ok(Summary {
+        strategy: strategy.name(),
+        matches: results.len(),
+        score: estimate(|r| r.score as f64)?,
+        wins: estimate(|r| r.wins as f64)?,
+        draws: estimate(|r| r.draws as f64)?,
+        losses: estimate(|r| r.losses as f64)?,
+    })
+}
+
+/// The summaries as a text table, one row per strategy, with `mean ±half-width` cells.
+pub fn summary_table(summaries: &[Summary]) -> String {
+    let cell = |e: &Estimate| format!("{:.1} ±{:.1}", e.mean, (e.high - e.low) / 2.0);
+    let mut s = format!(
+        "{:<10} {:>7} {:>18} {:>14} {:>14} {:>14}\n",
+        "strategy", "matches", "score", "wins", "draws", "losses"
+    );
+    for summary in summaries {
+        s.push_str(&format!(
+            "{:<10} {:>7} {:>18} {:>14} {:>14} {:>14}\n",
+            summary.strategy,
+            summary.matches,
+            cell(&summary.score),
+            cell(&summary.wins),
+            cell(&summary.draws),
+            cell(&summary.losses)
+        ));
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sim::{play_match, tournament, Frequency, Guide, Markov, Strategy, Uniform};
+    use crate::{read_guide_p1, ParseMode, RpsMove, Rules};
+
+    #[test]
+    fn test_guide_and_seeds() {
+        let rules = Rules::rps();
+        let games = read_guide_p1("src/test_input.txt", ParseMode::Strict)
+            .unwrap()
+            .value;
+        let opponent = games.iter().map(|g| g.opponent_move).collect::<Vec<_>>();
+        let summary = tournament(&rules, &opponent, &mut Guide::new(&games), 0..20).unwrap();
+        assert_eq!(15.0, summary.score.mean);
+        assert_eq!(summary.score.low, summary.score.high);
+
+        let mut uniform = Uniform;
+        assert_eq!(
+            play_match(&rules, &opponent, &mut uniform, 7).unwrap(),
+            play_match(&rules, &opponent, &mut uniform, 7).unwrap()
+        );
+        let summary = tournament(&rules, &opponent, &mut uniform, 0..200).unwrap();
+        assert!(summary.score.low < summary.score.mean && summary.score.mean < summary.score.high);
+        assert_eq!(
+            3.0,
+            summary.wins.mean + summary.draws.mean + summary.losses.mean
+        );
+        assert!(tournament(&rules, &opponent, &mut uniform, 5..5).is_err());
+    }
+
+    #[test]
+    fn test_predictors() {
+        let rules = Rules::rps();
+        let constant = vec![RpsMove::S; 100];
+        let cycle = (0..100).map(|i| RpsMove(i % 3)).collect::<Vec<_>>();
+        let strategies: [(&mut dyn Strategy, &[RpsMove]); 2] = [
+            (&mut Frequency::default(), &constant),
+            (&mut Markov::default(), &cycle),
+        ];
+        for (strategy, opponent) in strategies {
+            let result = play_match(&rules, opponent, strategy, 1).unwrap();
+            assert!(result.wins >= 97, "{} won {}", strategy.name(), result.wins);
+        }
+    }
+}
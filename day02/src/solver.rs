@@ -0,0 +1,558 @@
+//! The best score reachable against a known opponent sequence under constraints.
+//!
+//! [`solve`] runs a dynamic program over the rounds. Its state holds only what the active
+//! constraints need: the previous move for [`Constraints::no_repeat`], the losses so far
+//! for [`Constraints::max_losses`], and a usage counter for each shape whose
+//! [`Constraints::max_per_shape`] cap matters. Caps and the loss limit start out ignored;
+//! whenever the optimum breaks one, its counter is added and the program is run again.
+//! Dropping constraints only makes the problem easier, so the first optimum that breaks
+//! none of them is optimal.
+//!
+//! When the shape caps are the only constraint that binds, the order of the rounds does not
+//! matter: rounds against the same opponent move are interchangeable, so the caps are
+//! solved as a transportation problem from opponent moves to shapes with a min-cost flow
+//! ([`capped_assignment`]), whatever the number of rounds or the cap.
+//!
+//! Otherwise the table has one entry per round and state, and each shape counter multiplies
+//! its size by `max_per_shape + 1`. When every shape is counted, the last count is implied
+//! by the round number and is not stored. Caps combined with the repeat constraint or a
+//! binding loss limit solve the full input when they bind on a single shape; beyond that
+//! they may exceed [`STATE_LIMIT`] and are reported as an error rather than approximated.
+
+use anyhow::{anyhow, Result};
+
+use common::checked;
+
+use crate::{total_score, Game, RoundStrategy, RpsMove, Rules, DAY};
+
+/// The largest `rounds * states` table [`solve`] will build.
+pub const STATE_LIMIT: usize = 20_000_000;
+
+/// Limits on the player's moves. The default allows everything.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Constraints {
+    /// Play each shape at most this many times.
+    pub max_per_shape: Option<usize>,
+    /// Lose at most this many rounds.
+    pub max_losses: Option<usize>,
+    /// Never play the same shape in two consecutive rounds.
+    pub no_repeat: bool,
+}
+
+/// An optimal move sequence and its score.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub moves: Vec<RpsMove>,
+    pub score: u64,
+}
+
+/// The highest-scoring moves against `opponent` under `rules` and `constraints`.
+pub fn solve(rules: &Rules, opponent: &[RpsMove], constraints: Constraints) -> Result<Solution> {
+    let mut program = Program {
+        rules,
+        opponent,
+        constraints,
+        tracked: Vec::new(),
+        track_losses: false,
+    };
+    loop {
+        let solution = program
+            .run()?
+            .ok_or_else(|| anyhow!("No move sequence satisfies {:?}", constraints))?;
+        let mut satisfied = true;
+        if let Some(limit) = constraints.max_losses {
+            if losses(rules, &solution.moves, opponent) > limit {
+                program.track_losses = true;
+                satisfied = false;
+            }
+        }
+        if let Some(cap) = constraints.max_per_shape {
+            let over = rules
+                .moves()
+                .filter(|&m| solution.moves.iter().filter(|&&p| p == m).count() > cap)
+                .collect::<Vec<_>>();
+            if !over.is_empty() && !constraints.no_repeat && !program.track_losses {
+                let capped = capped_assignment(rules, opponent, cap)?
+                    .ok_or_else(|| anyhow!("No move sequence satisfies {:?}", constraints))?;
+                if constraints
+                    .max_losses
+                    .is_none_or(|limit| losses(rules, &capped.moves, opponent) <= limit)
+                {
+                    return Ok(capped);
+                }
+                program.track_losses = true;
+            }
+            for m in over {
+                if !program.tracked.contains(&m) {
+                    program.tracked.push(m);
+                }
+                satisfied = false;
+            }
+        }
+        if satisfied {
+            return Ok(solution);
+        }
+    }
+}
+
+/// The number of rounds `moves` lose against `opponent`.
+fn losses(rules: &Rules, moves: &[RpsMove], opponent: &[RpsMove]) -> usize {
+    moves
+        .iter()
+        .zip(opponent)
+        .filter(|(&p, &o)| rules.outcome(p, o) == RoundStrategy::Lose)
+        .count()
+}
+
+/// A residual arc of the flow network in [`capped_assignment`].
+struct Arc {
+    to: usize,
+    cap: usize,
+    cost: i64,
+    /// The index of the reverse arc in `to`'s list.
+    rev: usize,
+}
+
+/// The highest-scoring moves against `opponent` that play each shape at most `cap` times,
+/// or `None` if there are too many rounds for the cap.
+///
+/// Flow runs from a source to one node per opponent move (capacity: its rounds), on to one
+/// node per shape (cost: minus the round score) and into a sink (capacity: `cap`). The
+/// cheapest flow that routes every round is built by successive shortest paths.
+pub fn capped_assignment(
+    rules: &Rules,
+    opponent: &[RpsMove],
+    cap: usize,
+) -> Result<Option<Solution>> {
+    let n = rules.num_moves();
+    let (source, sink) = (0, 2 * n + 1);
+    let mut graph: Vec<Vec<Arc>> = (0..2 * n + 2).map(|_| Vec::new()).collect();
+    let add_arc = |graph: &mut Vec<Vec<Arc>>, from: usize, to: usize, cap, cost| {
+        let (rev_from, rev_to) = (graph[to].len(), graph[from].len());
+        graph[from].push(Arc {
+            to,
+            cap,
+            cost,
+            rev: rev_from,
+        });
+        graph[to].push(Arc {
+            to: from,
+            cap: 0,
+            cost: -cost,
+            rev: rev_to,
+        });
+    };
+    for o in rules.moves() {
+        let rounds = opponent.iter().filter(|&&p| p == o).count();
+        add_arc(&mut graph, source, 1 + o.0 as usize, rounds, 0);
+        for m in rules.moves() {
+            let game = Game {
+                player_move: m,
+                opponent_move: o,
+            };
+            let cost = -(game.score(rules)? as i64);
+            add_arc(
+                &mut graph,
+                1 + o.0 as usize,
+                1 + n + m.0 as usize,
+                rounds,
+                cost,
+            );
+        }
+    }
+    for m in rules.moves() {
+        add_arc(&mut graph, 1 + n + m.0 as usize, sink, cap, 0);
+    }
+
+    let mut routed = 0;
+    loop {
+        // Bellman-Ford, as the arc costs are negative; the residual graph has no negative
+        // cycles because every augmentation follows a cheapest path.
+        let mut dist = vec![None::<i64>; graph.len()];
+        let mut prev = vec![None::<(usize, usize)>; graph.len()];
+        dist[source] = Some(0);
+        for _ in 0..graph.len() {
+            let mut changed = false;
+            for node in 0..graph.len() {
+                let Some(d) = dist[node] else { continue };
+                for (i, arc) in graph[node].iter().enumerate() {
+                    if arc.cap > 0 && dist[arc.to].is_none_or(|t| d + arc.cost < t) {
+                        dist[arc.to] = Some(d + arc.cost);
+                        prev[arc.to] = Some((node, i));
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        if dist[sink].is_none() {
+            break;
+        }
+        let mut push = usize::MAX;
+        let mut node = sink;
+        while let Some((from, i)) = prev[node] {
+            push = push.min(graph[from][i].cap);
+            node = from;
+        }
+        let mut node = sink;
+        while let Some((from, i)) = prev[node] {
+            graph[from][i].cap -= push;
+            let (to, rev) = (graph[from][i].to, graph[from][i].rev);
+            graph[to][rev].cap += push;
+            node = from;
+        }
+        routed += push;
+    }
+    if routed < opponent.len() {
+        return Ok(None);
+    }
+
+    // The flow from each opponent node to each shape node is the capacity of its reverse arc.
+    let mut assigned = vec![vec![0; n]; n];
+    for o in rules.moves() {
+        for arc in graph[1 + o.0 as usize]
+            .iter()
+            .filter(|a| a.to > n && a.to <= 2 * n)
+        {
+            assigned[o.0 as usize][arc.to - 1 - n] = graph[arc.to][arc.rev].cap;
+        }
+    }
+    let moves = opponent
+        .iter()
+        .map(|o| {
+            let row = &mut assigned[o.0 as usize];
+            let m = row.iter().position(|&c| c > 0).unwrap_or_default();
+            row[m] -= 1;
+            RpsMove(m as u8)
+        })
+        .collect::<Vec<_>>();
+    let games = moves
+        .iter()
+        .zip(opponent)
+        .map(|(&player_move, &opponent_move)| Game {
+            player_move,
+            opponent_move,
+        })
+        .collect::<Vec<_>>();
+    Ok(Some(Solution {
+        score: total_score(rules, &games)?,
+        moves,
+    }))
+}
+
+/// The dynamic program with a chosen subset of the constraints.
+struct Program<'a> {
+    rules: &'a Rules,
+    opponent: &'a [RpsMove],
+    constraints: Constraints,
+    /// Shapes whose usage is counted and capped.
+    tracked: Vec<RpsMove>,
+    track_losses: bool,
+}
+
+/// A decoded DP state. `last` is `num_moves` before the first round.
+struct State {
+    last: usize,
+    losses: usize,
+    counts: Vec<usize>,
+}
+
+impl Program<'_> {
+    fn last_radix(&self) -> usize {
+        if self.constraints.no_repeat {
+            self.rules.num_moves() + 1
+        } else {
+            1
+        }
+    }
+
+    fn loss_radix(&self) -> usize {
+        match (self.track_losses, self.constraints.max_losses) {
+            (true, Some(limit)) => limit + 1,
+            _ => 1,
+        }
+    }
+
+    fn count_radix(&self) -> usize {
+        self.constraints.max_per_shape.unwrap_or(0) + 1
+    }
+
+    /// The tracked shapes whose counts are stored in the state. When every shape is
+    /// tracked, the last one's count is the round number minus the others.
+    fn counted(&self) -> &[RpsMove] {
+        match self.implied() {
+            Some(_) => &self.tracked[..self.tracked.len() - 1],
+            None => &self.tracked,
+        }
+    }
+
+    fn implied(&self) -> Option<RpsMove> {
+        if self.tracked.len() == self.rules.num_moves() {
+            self.tracked.last().copied()
+        } else {
+            None
+        }
+    }
+
+    fn num_states(&self) -> Option<usize> {
+        self.counted().iter().try_fold(
+            self.last_radix().checked_mul(self.loss_radix())?,
+            |acc, _| acc.checked_mul(self.count_radix()),
+        )
+    }
+
+    fn encode(&self, state: &State) -> usize {
+        let last = if self.constraints.no_repeat {
+            state.last
+        } else {
+            0
+        };
+        let losses = if self.track_losses { state.losses } else { 0 };
+        state
+            .counts
+            .iter()
+            .fold(last * self.loss_radix() + losses, |acc, &c| {
+                acc * self.count_radix() + c
+            })
+    }
+
+    fn decode(&self, mut idx: usize) -> State {
+        let mut counts = vec![0; self.counted().len()];
+        for c in counts.iter_mut().rev() {
+            *c = idx % self.count_radix();
+            idx /= self.count_radix();
+        }
+        State {
+            losses: idx % self.loss_radix(),
+            last: idx / self.loss_radix(),
+            counts,
+        }
+    }
+
+    /// The best solution, or `None` if no sequence satisfies the tracked constraints.
+    fn run(&self) -> Result<Option<Solution>> {
+        let num_states = self
+            .num_states()
+            .filter(|&s| s.saturating_mul(self.opponent.len().max(1)) <= STATE_LIMIT)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Solving {:?} needs more than {} DP states",
+                    self.constraints,
+                    STATE_LIMIT
+                )
+            })?;
+        let num_moves = self.rules.num_moves();
+        let start = State {
+            last: num_moves,
+            losses: 0,
+            counts: vec![0; self.counted().len()],
+        };
+        let mut best = vec![None::<u64>; num_states];
+        best[self.encode(&start)] = Some(0);
+        // For each round and reached state: the move played and the previous state.
+        let mut choices = Vec::with_capacity(self.opponent.len());
+        for (round, &opponent_move) in self.opponent.iter().enumerate() {
+            let mut next = vec![None::<u64>; num_states];
+            let mut choice = vec![(0u8, 0u32); num_states];
+            for (idx, value) in best.iter().enumerate() {
+                let Some(value) = *value else { continue };
+                let state = self.decode(idx);
+                for m in self.rules.moves() {
+                    if self.constraints.no_repeat && state.last == m.0 as usize {
+                        continue;
+                    }
+                    let lost = self.rules.outcome(m, opponent_move) == RoundStrategy::Lose;
+                    let mut after = State {
+                        last: m.0 as usize,
+                        losses: state.losses + usize::from(lost),
+                        counts: state.counts.clone(),
+                    };
+                    if self.track_losses && after.losses >= self.loss_radix() {
+                        continue;
+                    }
+                    if let Some(i) = self.counted().iter().position(|&t| t == m) {
+                        after.counts[i] += 1;
+                        if after.counts[i] >= self.count_radix() {
+                            continue;
+                        }
+                    } else if self.implied() == Some(m)
+                        && round - state.counts.iter().sum::<usize>() + 1 >= self.count_radix()
+                    {
+                        continue;
+                    }
+                    let game = Game {
+                        player_move: m,
+                        opponent_move,
+                    };
+                    let score = checked::add(DAY, "best score", value, game.score(self.rules)?)?;
+                    let after = self.encode(&after);
+                    if next[after].is_none_or(|v| score > v) {
+                        next[after] = Some(score);
+                        choice[after] = (m.0, idx as u32);
+                    }
+                }
+            }
+            choices.push(choice);
+            best = next;
+        }
+
+        let Some((mut idx, score)) = best
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| v.map(|v| (i, v)))
+            .max_by_key(|&(_, v)| v)
+        else {
+            return Ok(None);
+        };
+        let mut moves = Vec::with_capacity(choices.len());
+        for choice in choices.iter().rev() {
+            let (m, prev) = choice[idx];
+            moves.push(RpsMove(m));
+            idx = prev as usize;
+        }
+        moves.reverse();
+        Ok(Some(Solution { moves, score }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::rng::Rng;
+
+    use crate::solver::{solve, Constraints, Program};
+    use crate::{read_guide_p1, total_score, Game, ParseMode, RoundStrategy, RpsMove, Rules};
+
+    #[test]
+    fn test_solve() {
+        let rules = Rules::rps();
+        let games = read_guide_p1("src/test_input.txt", ParseMode::Strict)
+            .unwrap()
+            .value;
+        let opponent = games.iter().map(|g| g.opponent_move).collect::<Vec<_>>();
+        assert_eq!(
+            24,
+            solve(&rules, &opponent, Constraints::default())
+                .unwrap()
+                .score
+        );
+
+        let rocks = [RpsMove::R; 4];
+        let no_repeat = Constraints {
+            no_repeat: true,
+            ..Default::default()
+        };
+        let solution = solve(&rules, &rocks, no_repeat).unwrap();
+        assert_eq!(24, solution.score);
+        assert!(solution.moves.windows(2).all(|w| w[0] != w[1]));
+
+        let once_each = Constraints {
+            max_per_shape: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(15, solve(&rules, &rocks[..3], once_each).unwrap().score);
+        let no_losses = Constraints {
+            max_losses: Some(0),
+            ..once_each
+        };
+        assert!(solve(&rules, &rocks[..3], no_losses).is_err());
+    }
+
+    #[test]
+    fn test_full_input_caps() {
+        let rules = Rules::rps();
+        let games = read_guide_p1("src/input.txt", ParseMode::Strict)
+            .unwrap()
+            .value;
+        let opponent = games.iter().map(|g| g.opponent_move).collect::<Vec<_>>();
+        let tight = opponent.len().div_ceil(rules.num_moves());
+        let mut scores = Vec::new();
+        for cap in [tight, 900, opponent.len()] {
+            let constraints = Constraints {
+                max_per_shape: Some(cap),
+                ..Default::default()
+            };
+            let solution = solve(&rules, &opponent, constraints).unwrap();
+            assert!(rules
+                .moves()
+                .all(|m| solution.moves.iter().filter(|&&p| p == m).count() <= cap));
+            let games = solution
+                .moves
+                .iter()
+                .zip(&opponent)
+                .map(|(&player_move, &opponent_move)| Game {
+                    player_move,
+                    opponent_move,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(solution.score, total_score(&rules, &games).unwrap());
+            scores.push(solution.score);
+        }
+        assert!(scores.windows(2).all(|w| w[0] < w[1]));
+        let too_tight = Constraints {
+            max_per_shape: Some(tight - 1),
+            ..Default::default()
+        };
+        assert!(solve(&rules, &opponent, too_tight).is_err());
+    }
+
+    #[test]
+    fn test_solve_matches_brute_force() {
+        let rules = Rules::rps();
+        let mut rng = Rng::new(39);
+        for _ in 0..60 {
+            let opponent = (0..8)
+                .map(|_| RpsMove(rng.below(3) as u8))
+                .collect::<Vec<_>>();
+            let constraints = Constraints {
+                max_per_shape: [None, Some(3), Some(4)][rng.below(3)],
+                max_losses: [None, Some(0), Some(1)][rng.below(3)],
+                no_repeat: rng.below(2) == 1,
+            };
+            let mut best = None;
+            for code in 0..3usize.pow(opponent.len() as u32) {
+                let moves = (0..opponent.len())
+                    .map(|i| RpsMove((code / 3usize.pow(i as u32) % 3) as u8))
+                    .collect::<Vec<_>>();
+                let ok = constraints.max_per_shape.is_none_or(|k| {
+                    rules
+                        .moves()
+                        .all(|m| moves.iter().filter(|&&p| p == m).count() <= k)
+                }) && constraints.max_losses.is_none_or(|l| {
+                    moves
+                        .iter()
+                        .zip(&opponent)
+                        .filter(|(&p, &o)| rules.outcome(p, o) == RoundStrategy::Lose)
+                        .count()
+                        <= l
+                }) && !(constraints.no_repeat && moves.windows(2).any(|w| w[0] == w[1]));
+                if ok {
+                    let games = moves
+                        .iter()
+                        .zip(&opponent)
+                        .map(|(&player_move, &opponent_move)| Game {
+                            player_move,
+                            opponent_move,
+                        })
+                        .collect::<Vec<_>>();
+                    let score = total_score(&rules, &games).unwrap();
+                    best = best.max(Some(score));
+                }
+            }
+            let solved = solve(&rules, &opponent, constraints).ok().map(|s| s.score);
+            assert_eq!(best, solved, "{:?} against {:?}", constraints, opponent);
+            if constraints.max_per_shape.is_some() {
+                let counted = Program {
+                    rules: &rules,
+                    opponent: &opponent,
+                    constraints,
+                    tracked: rules.moves().collect(),
+                    track_losses: constraints.max_losses.is_some(),
+                };
+                let solved = counted.run().unwrap().map(|s| s.score);
+                assert_eq!(best, solved, "{:?} against {:?}", constraints, opponent);
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Compares the `ItemSet` solvers with the `HashSet<char>` versions they replaced on a large
+//! generated input. Run with `cargo bench -p day3`.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use common::rng::Rng;
+use day3::{
+    ascii_to_priority, badge_priority_sum, misplaced_priority_sum, parse_rucksack_list_p1,
+    parse_rucksack_list_p2, GROUP_SIZE,
+};
+
+const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const GROUPS: usize = 100_000;
+const RUNS: u32 = 5;
+
+fn shuffle(rng: &mut Rng, items: &mut [u8]) {
+    for i in (1..items.len()).rev() {
+        items.swap(i, rng.below(i + 1));
+    }
+}
+
+/// `half` items: `fixed` followed by random picks from `pool`, shuffled.
+fn compartment(rng: &mut Rng, fixed: &[u8], pool: &[u8], half: usize) -> Vec<u8> {
+    let mut items = fixed.to_vec();
+    while items.len() < half {
+        items.push(match pool.len() {
+            0 => fixed[0],
+            n => pool[rng.below(n)],
+        });
+    }
+    shuffle(rng, &mut items);
+    items
+}
+
+/// Groups of three rucksacks whose only common item is the badge, and whose compartments
+/// share exactly one item.
+fn generate(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut data = String::new();
+    for _ in 0..GROUPS {
+        let badge = ITEMS[rng.below(ITEMS.len())];
+        // Every other item is left out of one rucksack, so only the badge is in all three.
+        let left_out = ITEMS.iter().map(|_| rng.below(3)).collect::<Vec<usize>>();
+        for elf in 0..3 {
+            let mut allowed = ITEMS
+                .iter()
+                .zip(&left_out)
+                .filter(|&(&item, &l)| item != badge && l != elf)
+                .map(|(&item, _)| item)
+                .collect::<Vec<u8>>();
+            let shared = match rng.below(allowed.len() + 1) {
+                0 => badge,
+                i => allowed.swap_remove(i - 1),
+            };
+            shuffle(&mut rng, &mut allowed);
+            let (only_c1, only_c2) = allowed.split_at(allowed.len() / 2);
+            let (mut fixed1, mut fixed2) = (vec![shared], vec![shared]);
+            if shared != badge {
+                if rng.below(2) == 0 {
+                    fixed1.push(badge);
+                } else {
+                    fixed2.push(badge);
+                }
+            }
+            let half = 8 + rng.below(16);
+            let c1 = compartment(&mut rng, &fixed1, only_c1, half);
+            let c2 = compartment(&mut rng, &fixed2, only_c2, half);
+            data.push_str(std::str::from_utf8(&c1).unwrap());
+            data.push_str(std::str::from_utf8(&c2).unwrap());
+            data.push('\n');
+        }
+    }
+    data
+}
+
+fn hashset_part_one(data: &str) -> Result<u64> {
+    let mut sum = 0;
+    for line in data.lines() {
+        let (c1, c2) = line.split_at(line.len() / 2);
+        let c1 = c1.chars().collect::<HashSet<char>>();
+        let c2 = c2.chars().collect::<HashSet<char>>();
+        let item = c1
+            .intersection(&c2)
+            .next()
+            .ok_or_else(|| anyhow!("failed to find common item"))?;
+        sum += ascii_to_priority(*item)?;
+    }
+    Ok(sum)
+}
+
+fn hashset_part_two(data: &str) -> Result<u64> {
+    let lines = data.lines().collect::<Vec<&str>>();
+    let mut sum = 0;
+    for triple in lines.chunks(3) {
+        let sets = triple
+            .iter()
+            .map(|l| l.chars().collect::<HashSet<char>>())
+            .collect::<Vec<HashSet<char>>>();
+        let tmp = sets[0]
+            .intersection(&sets[1])
+            .copied()
+            .collect::<HashSet<char>>();
+        let badge = tmp
+            .intersection(&sets[2])
+            .next()
+            .ok_or_else(|| anyhow!("failed to find common badge"))?;
+        sum += ascii_to_priority(*badge)?;
+    }
+    Ok(sum)
+}
+
+type Solver<'a> = &'a dyn Fn() -> Result<u64>;
+
+/// The fastest of `RUNS` runs of `f`, and its result.
+fn time<T>(f: impl Fn() -> Result<T>) -> Result<(Duration, T)> {
+    let mut best = None;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        let value = f()?;
+        let elapsed = start.elapsed();
+        if best.as_ref().is_none_or(|(d, _)| elapsed < *d) {
+            best = Some((elapsed, value));
+        }
+    }
+    Ok(best.expect("at least one run"))
+}
+
+fn main() -> Result<()> {
+    let data = generate(3);
+    println!("{} rucksacks", GROUPS * 3);
+    let parts: [(&str, Solver, Solver); 2] = [
+        ("part one", &|| hashset_part_one(&data), &|| {
+            misplaced_priority_sum(&parse_rucksack_list_p1(&data)?)
+        }),
+        ("part two", &|| hashset_part_two(&data), &|| {
+            badge_priority_sum(&parse_rucksack_list_p2(&data, GROUP_SIZE)?.badges)
+        }),
+    ];
+    for (name, hashset, item_set) in parts {
+        let (expected, actual) = (hashset()?, item_set()?);
+        if expected != actual {
+            return Err(anyhow!(
+                "{name}: HashSet gives {expected} but ItemSet gives {actual}"
+            ));
+        }
+        let (hashset_time, _) = time(hashset)?;
+        let (item_set_time, _) = time(item_set)?;
+        println!(
+            "{name}: HashSet {:>8.2?}  ItemSet {:>8.2?}  ({:.1}x)",
+            hashset_time,
+            item_set_time,
+            hashset_time.as_secs_f64() / item_set_time.as_secs_f64()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,458 @@
+//! Day 3: Rucksack Reorganization.
+//!
+//! Each line is a rucksack whose two halves are its compartments. Items are letters, with
+//! priorities 1-26 for `a-z` and 27-52 for `A-Z`. Sets of items are [`ItemSet`] bitmasks.
+//! Consecutive rucksacks form groups of [`GROUP_SIZE`] elves, or any size given to
+//! [`parse_groups`]; a group whose rucksacks share exactly one item has that item as badge.
+
+pub mod repair;
+
+use std::fmt::{Debug, Display};
+
+use anyhow::{anyhow, Result};
+use common::checked;
+
+const DAY: &str = "day03";
+
+/// The number of elves in a group in the puzzle.
+pub const GROUP_SIZE: usize = 3;
+
+/// The priority of item `c`, or an error if it is not an ASCII letter.
+pub fn ascii_to_priority(c: char) -> Result<u64> {
+    if c.is_ascii_lowercase() {
+        Ok((c as u8 - 96) as u64)
+    } else if c.is_ascii_uppercase() {
+        Ok((c as u8 - 38) as u64)
+    } else {
+        Err(anyhow!("Item {:?} has no priority", c))
+    }
+}
+
+/// The item with priority `p`, which must be in `1..=52`.
+fn priority_to_ascii(p: u32) -> char {
+    if p <= 26 {
+        (b'a' + p as u8 - 1) as char
+    } else {
+        (b'A' + p as u8 - 27) as char
+    }
+}
+
+/// A set of item types. Bit `p` is set when the item with priority `p` is in the set.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    /// The set holding no items.
+    pub const EMPTY: Self = Self(0);
+    /// The set holding all 52 item types.
+    pub const ALL: Self = Self(((1 << 52) - 1) << 1);
+
+    /// The set of items in `items`, or an error naming the first non-letter.
+    pub fn from_items(items: &str) -> Result<Self> {
+        let mut set = Self::EMPTY;
+        for c in items.chars() {
+            set.insert(c)?;
+        }
+        Ok(set)
+    }
+
+    /// Add item `c`, or fail if it is not an ASCII letter.
+    pub fn insert(&mut self, c: char) -> Result<()> {
+        self.0 |= 1 << ascii_to_priority(c)?;
+        Ok(())
+    }
+
+    /// Whether item `c` is in the set.
+    pub fn contains(self, c: char) -> bool {
+        ascii_to_priority(c).is_ok_and(|p| self.0 & (1 << p) != 0)
+    }
+
+    /// The items in either set.
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// The items in both sets.
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// The number of items in the set.
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Whether the set holds no items.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The items in priority order.
+    pub fn iter(self) -> impl Iterator<Item = char> {
+        self.priorities().map(|p| priority_to_ascii(p as u32))
+    }
+
+    /// The priorities of the items, in increasing order.
+    pub fn priorities(self) -> impl Iterator<Item = u64> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let p = bits.trailing_zeros();
+            bits &= bits - 1;
+            Some(p as u64)
+        })
+    }
+}
+
+impl Debug for ItemSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// A rucksack split into its two compartments.
+pub struct RuckSack {
+    c1: String,
+    c2: String,
+}
+
+impl RuckSack {
+    /// The item sets of the two compartments.
+    pub fn compartments(&self) -> Result<(ItemSet, ItemSet)> {
+        Ok((
+            ItemSet::from_items(&self.c1)?,
+            ItemSet::from_items(&self.c2)?,
+        ))
+    }
+
+    /// Every item type packed in both compartments.
+    pub fn common_items(&self) -> Result<ItemSet> {
+        let (c1, c2) = self.compartments()?;
+        Ok(c1.intersection(c2))
+    }
+
+    /// The item type packed in both compartments. If there are several, the one with the
+    /// lowest priority.
+    pub fn find_intersecting_item(&self) -> Result<char> {
+        self.common_items()?
+            .iter()
+            .next()
+            .ok_or_else(|| anyhow!("failed to find common item"))
+    }
+}
+
+/// The summed priority of the item found in both compartments of each rucksack.
+pub fn misplaced_priority_sum(rucksacks: &[RuckSack]) -> Result<u64> {
+    rucksacks
+        .iter()
+        .map(|r| ascii_to_priority(r.find_intersecting_item()?))
+        .collect::<Result<Vec<u64>>>()
+        .and_then(|p| Ok(checked::sum(DAY, "misplaced item priority sum", p)?))
+}
+
+/// The summed priority of the group badges found by [`find_badges`].
+pub fn badge_priority_sum(badges: &[char]) -> Result<u64> {
+    badges
+        .iter()
+        .copied()
+        .map(ascii_to_priority)
+        .collect::<Result<Vec<u64>>>()
+        .and_then(|p| Ok(checked::sum(DAY, "badge priority sum", p)?))
+}
+
+/// Read `input` as a list of rucksacks.
+pub fn read_rucksack_list_p1(input: &str) -> Result<Vec<RuckSack>> {
+    parse_rucksack_list_p1(&std::fs::read_to_string(input)?)
+}
+
+/// Parse one rucksack per line, rejecting non-letter items and lines of odd length.
+pub fn parse_rucksack_list_p1(data: &str) -> Result<Vec<RuckSack>> {
+    let mut sacks = Vec::new();
+    for (idx, line) in data.lines().enumerate() {
+        if let Some(c) = line.chars().find(|c| !c.is_ascii_alphabetic()) {
+            return Err(anyhow!(
+                "Unexpected item {:?} in rucksack {} on line {}",
+                c,
+                line,
+                idx + 1
+            ));
+        }
+        if !line.len().is_multiple_of(2) {
+            return Err(anyhow!(
+                "Rucksack {} on line {} has an odd number of items",
+                line,
+                idx + 1
+            ));
+        }
+        let rucksack_size = line.len();
+        let compartment_size = rucksack_size / 2;
+        sacks.push(RuckSack {
+            c1: line[0..compartment_size].to_string(),
+            c2: line[compartment_size..rucksack_size].to_string(),
+        });
+    }
+    Ok(sacks)
+}
+
+/// The items carried by every rucksack of a group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    /// 1-based position of the group in the list.
+    pub number: usize,
+    /// 1-based line of the group's first rucksack.
+    pub first_line: usize,
+    /// The number of rucksacks in the group; less than the group size only at the end.
+    pub sacks: usize,
+    pub common: ItemSet,
+}
+
+impl Group {
+    /// The group's badge, if its rucksacks share exactly one item.
+    pub fn badge(&self) -> Option<char> {
+        match self.common.len() {
+            1 => self.common.iter().next(),
+            _ => None,
+        }
+    }
+
+    /// Every shared item with its priority, in priority order.
+    pub fn common_items(&self) -> Vec<(char, u64)> {
+        self.common.iter().zip(self.common.priorities()).collect()
+    }
+}
+
+impl Display for Group {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "group {} (lines {}-{}):",
+            self.number,
+            self.first_line,
+            self.first_line + self.sacks - 1
+        )?;
+        for (item, priority) in self.common_items() {
+            write!(f, " {item} ({priority})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Why a group has no badge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupIssue {
+    /// The rucksacks share no item.
+    NoCommonItem,
+    /// The rucksacks share more than one item.
+    SeveralCommonItems(ItemSet),
+    /// The list ended before the group was full.
+    Incomplete { sacks: usize, expected: usize },
+}
+
+/// A group without a single badge, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupDiagnostic {
+    pub group: usize,
+    pub first_line: usize,
+    pub issue: GroupIssue,
+}
+
+impl Display for GroupDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "group {} (line {}) ", self.group, self.first_line)?;
+        match &self.issue {
+            GroupIssue::NoCommonItem => write!(f, "shares no item"),
+            GroupIssue::SeveralCommonItems(items) => {
+                write!(f, "shares {} items {:?}", items.len(), items)
+            }
+            GroupIssue::Incomplete { sacks, expected } => {
+                write!(f, "has {} of {} rucksacks", sacks, expected)
+            }
+        }
+    }
+}
+
+/// The badges of the groups that have one, and diagnostics for those that do not.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Badges {
+    pub badges: Vec<char>,
+    pub diagnostics: Vec<GroupDiagnostic>,
+}
+
+/// Split the rucksacks into groups of `size` consecutive lines and intersect each group.
+/// The last group may be smaller.
+pub fn parse_groups(data: &str, size: usize) -> Result<Vec<Group>> {
+    if size == 0 {
+        return Err(anyhow!("Group size must be at least 1"));
+    }
+    let sets = data
+        .lines()
+        .map(ItemSet::from_items)
+        .collect::<Result<Vec<ItemSet>>>()?;
+    Ok(sets
+        .chunks(size)
+        .enumerate()
+        .map(|(idx, group)| Group {
+            number: idx + 1,
+            first_line: idx * size + 1,
+            sacks: group.len(),
+            common: group
+                .iter()
+                .fold(ItemSet::ALL, |acc, &s| acc.intersection(s)),
+        })
+        .collect())
+}
+
+/// The badge of each full group of `size` rucksacks with exactly one shared item.
+pub fn find_badges(groups: &[Group], size: usize) -> Badges {
+    let mut badges = Badges::default();
+    for group in groups {
+        let issue = if group.sacks < size {
+            GroupIssue::Incomplete {
+                sacks: group.sacks,
+                expected: size,
+            }
+        } else if let Some(badge) = group.badge() {
+            badges.badges.push(badge);
+            continue;
+        } else if group.common.is_empty() {
+            GroupIssue::NoCommonItem
+        } else {
+            GroupIssue::SeveralCommonItems(group.common)
+        };
+        badges.diagnostics.push(GroupDiagnostic {
+            group: group.number,
+            first_line: group.first_line,
+            issue,
+        });
+    }
+    badges
+}
+
+/// Read `input` and find the badge of each group of `size` elves.
+pub fn read_rucksack_list_p2(input: &str, size: usize) -> Result<Badges> {
+    parse_rucksack_list_p2(&std::fs::read_to_string(input)?, size)
+}
+
+/// The badges of the groups of `size` consecutive rucksacks.
+pub fn parse_rucksack_list_p2(data: &str, size: usize) -> Result<Badges> {
+    Ok(find_badges(&parse_groups(data, size)?, size))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use common::fuzz::check_parser;
+
+    use crate::{
+        ascii_to_priority, parse_groups, parse_rucksack_list_p1, parse_rucksack_list_p2,
+        read_rucksack_list_p1, read_rucksack_list_p2, GroupIssue, ItemSet, GROUP_SIZE,
+    };
+
+    #[test]
+    fn test_part_one() {
+        assert_eq!(
+            157,
+            read_rucksack_list_p1("src/test_input.txt")
+                .unwrap()
+                .iter()
+                .map(|r| ascii_to_priority(r.find_intersecting_item().unwrap()).unwrap())
+                .sum::<u64>()
+        )
+    }
+
+    #[test]
+    fn test_part_two() {
+        assert_eq!(
+            70,
+            read_rucksack_list_p2("src/test_input.txt", GROUP_SIZE)
+                .unwrap()
+                .badges
+                .iter()
+                .copied()
+                .map(|c| ascii_to_priority(c).unwrap())
+                .sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn test_item_set() {
+        let a = ItemSet::from_items("vJrwpWtwJgWr").unwrap();
+        let b = ItemSet::from_items("hcsFMMfFFhFp").unwrap();
+        assert_eq!(vec!['p'], a.intersection(b).iter().collect::<Vec<char>>());
+        assert_eq!(
+            vec!['g', 'p', 'r', 't', 'v', 'w', 'J', 'W'],
+            a.iter().collect::<Vec<char>>()
+        );
+        assert_eq!(
+            vec![7, 16, 18, 20, 22, 23, 36, 49],
+            a.priorities().collect::<Vec<u64>>()
+        );
+        assert_eq!(8 + 7 - 1, a.union(b).len());
+        assert!(a.contains('J') && !a.contains('j') && !a.contains('1'));
+        assert_eq!(52, ItemSet::ALL.len());
+        assert!(ItemSet::EMPTY.is_empty());
+        assert!(ItemSet::from_items("ab1").is_err());
+    }
+
+    #[test]
+    fn test_groups() {
+        let data = std::fs::read_to_string("src/test_input.txt").unwrap();
+        let pairs = parse_groups(&data, 2).unwrap();
+        assert_eq!(3, pairs.len());
+        assert_eq!(5, pairs[0].common_items().len());
+        assert_eq!(
+            "group 3 (lines 5-6): G (33) J (36) Z (52)",
+            pairs[2].to_string()
+        );
+
+        let badges = parse_rucksack_list_p2("abc\nbcd\nxy\nyz\nq\n", 2).unwrap();
+        assert_eq!(vec!['y'], badges.badges);
+        assert_eq!(2, badges.diagnostics.len());
+        assert!(matches!(
+            badges.diagnostics[0].issue,
+            GroupIssue::SeveralCommonItems(items) if items.len() == 2
+        ));
+
+        let badges = parse_rucksack_list_p2(&data, 4).unwrap();
+        assert!(badges.badges.is_empty());
+        assert_eq!(
+            vec![
+                GroupIssue::NoCommonItem,
+                GroupIssue::Incomplete {
+                    sacks: 2,
+                    expected: 4
+                }
+            ],
+            badges
+                .diagnostics
+                .iter()
+                .map(|d| d.issue.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "group 2 (line 5) has 2 of 4 rucksacks",
+            badges.diagnostics[1].to_string()
+        );
+        assert!(parse_groups(&data, 0).is_err());
+    }
+
+    #[test]
+    fn test_fuzz_parsers() {
+        let base = std::fs::read_to_string("src/test_input.txt").unwrap();
+        check_parser("parse_rucksack_list_p1", &base, 3, 2_000, |s| {
+            parse_rucksack_list_p1(s)?
+                .iter()
+                .map(|r| ascii_to_priority(r.find_intersecting_item()?))
+                .sum::<Result<u64>>()
+        });
+        check_parser("parse_rucksack_list_p2", &base, 3, 2_000, |s| {
+            parse_rucksack_list_p2(s, GROUP_SIZE)?
+                .badges
+                .into_iter()
+                .map(ascii_to_priority)
+                .sum::<Result<u64>>()
+        });
+    }
+}
@@ -0,0 +1,190 @@
+//! Rearranging rucksacks so that no item type is packed in both compartments.
+//!
+//! A move swaps one item of the first compartment with one of the second, so both keep
+//! their size. [`repair`] decides for each item type which compartment holds all of it:
+//! types sent to the first compartment must fill it exactly, and each item packed on the
+//! wrong side costs half a move. A subset-sum program over the item types finds the
+//! cheapest split. Items that stay keep their positions; the moved ones fill the freed
+//! positions in their original order.
+
+use anyhow::{anyhow, Result};
+
+use crate::{ascii_to_priority, RuckSack};
+
+/// A rearranged rucksack and the moves that produced it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repair {
+    pub line: String,
+    pub moves: usize,
+}
+
+/// The repairs of a whole list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepairPlan {
+    pub repairs: Vec<Repair>,
+    pub total_moves: usize,
+}
+
+/// The fewest swaps that leave no item type in both compartments of `sack`, or an error
+/// if the item counts cannot be split into two equal halves.
+pub fn repair(sack: &RuckSack) -> Result<Repair> {
+    let half = sack.c1.len();
+    let mut counts = [[0usize; 2]; 53];
+    for (side, items) in [&sack.c1, &sack.c2].into_iter().enumerate() {
+        for c in items.chars() {
+            counts[ascii_to_priority(c)? as usize][side] += 1;
+        }
+    }
+    let types = (1..counts.len())
+        .filter(|&p| counts[p] != [0, 0])
+        .collect::<Vec<usize>>();
+
+    // best[s]: fewest items moved into the first compartment when the types chosen so far
+    // put `s` items there. keep[i][s] records whether type `i` stayed in the first one.
+    let mut best = vec![None::<usize>; half + 1];
+    best[0] = Some(0);
+    let mut keep = Vec::with_capacity(types.len());
+    for &p in &types {
+        let [first, second] = counts[p];
+        let mut next = vec![None::<usize>; half + 1];
+        let mut choice = vec![false; half + 1];
+        for (s, cost) in best.iter().enumerate() {
+            let Some(cost) = *cost else { continue };
+            if next[s].is_none_or(|c| cost < c) {
+                next[s] = Some(cost);
+                choice[s] = false;
+            }
+            let s = s + first + second;
+            if s <= half && next[s].is_none_or(|c| cost + second < c) {
+                next[s] = Some(cost + second);
+                choice[s] = true;
+            }
+        }
+        keep.push(choice);
+        best = next;
+    }
+    let moves = best[half].ok_or_else(|| {
+        anyhow!(
+            "Rucksack {}{} cannot be split into two compartments without shared items",
+            sack.c1,
+            sack.c2
+        )
+    })?;
+
+    let mut in_first = [false; 53];
+    let mut s = half;
+    for (i, &p) in types.iter().enumerate().rev() {
+        if keep[i][s] {
+            in_first[p] = true;
+            s -= counts[p][0] + counts[p][1];
+        }
+    }
+    let wrong_side =
+        |c: char, first: bool| ascii_to_priority(c).is_ok_and(|p| in_first[p as usize] != first);
+    let mut into_first = sack.c2.chars().filter(|&c| wrong_side(c, false));
+    let mut into_second = sack.c1.chars().filter(|&c| wrong_side(c, true));
+    let mut line = String::with_capacity(2 * half);
+    for c in sack.c1.chars() {
+        line.push(match wrong_side(c, true) {
+            true => into_first.next().unwrap_or(c),
+            false => c,
+        });
+    }
+    for c in sack.c2.chars() {
+        line.push(match wrong_side(c, false) {
+            true => into_second.next().unwrap_or(c),
+            false => c,
+        });
+    }
+    Ok(Repair { line, moves })
+}
+
+/// Repair every rucksack in `sacks`.
+pub fn plan(sacks: &[RuckSack]) -> Result<RepairPlan> {
+    let repairs = sacks
+        .iter()
+        .enumerate()
+        .map(|(idx, sack)| repair(sack).map_err(|e| anyhow!("Line {}: {}", idx + 1, e)))
+        .collect::<Result<Vec<Repair>>>()?;
+    Ok(RepairPlan {
+        total_moves: repairs.iter().map(|r| r.moves).sum(),
+        repairs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use common::rng::Rng;
+
+    use crate::repair::{plan, repair};
+    use crate::{parse_rucksack_list_p1, read_rucksack_list_p1};
+
+    /// The fewest swaps found by trying every split of the items into two halves.
+    fn brute_force(line: &str) -> Option<usize> {
+        let items = line.as_bytes();
+        let half = items.len() / 2;
+        (0u32..1 << items.len())
+            .filter(|mask| mask.count_ones() as usize == half)
+            .filter(|mask| {
+                (0..items.len()).all(|i| {
+                    (0..items.len())
+                        .all(|j| items[i] != items[j] || (mask >> i & 1) == (mask >> j & 1))
+                })
+            })
+            .map(|mask| (0..half).filter(|&i| mask >> i & 1 == 0).count())
+            .min()
+    }
+
+    #[test]
+    fn test_repair() {
+        let sacks = read_rucksack_list_p1("src/test_input.txt").unwrap();
+        let plan = plan(&sacks).unwrap();
+        assert_eq!(6, plan.repairs.len());
+        for (sack, fixed) in sacks.iter().zip(&plan.repairs) {
+            let original = format!("{}{}", sack.c1, sack.c2);
+            let mut a = original.chars().collect::<Vec<char>>();
+            let mut b = fixed.line.chars().collect::<Vec<char>>();
+            a.sort_unstable();
+            b.sort_unstable();
+            assert_eq!(a, b);
+            let fixed = parse_rucksack_list_p1(&fixed.line).unwrap();
+            assert!(fixed[0].common_items().unwrap().is_empty());
+        }
+        assert_eq!(1, plan.repairs[0].moves);
+        assert_eq!(
+            plan.repairs.iter().map(|r| r.moves).sum::<usize>(),
+            plan.total_moves
+        );
+
+        assert!(repair(&parse_rucksack_list_p1("aaab").unwrap()[0]).is_err());
+        assert!(parse_rucksack_list_p1("abc").is_err());
+        assert!(parse_rucksack_list_p1("ab1c").is_err());
+    }
+
+    #[test]
+    fn test_repair_matches_brute_force() {
+        let mut rng = Rng::new(42);
+        for _ in 0..200 {
+            let len = 2 * (1 + rng.below(6));
+            let line = (0..len)
+                .map(|_| (b'a' + rng.below(4) as u8) as char)
+                .collect::<String>();
+            let sack = &parse_rucksack_list_p1(&line).unwrap()[0];
+            let repaired = repair(sack).ok();
+            assert_eq!(
+                brute_force(&line),
+                repaired.as_ref().map(|r| r.moves),
+                "{}",
+                line
+            );
+            if let Some(r) = repaired {
+                let moved = line
+                    .chars()
+                    .zip(r.line.chars())
+                    .filter(|(a, b)| a != b)
+                    .count();
+                assert!(moved <= 2 * r.moves, "{} -> {}", line, r.line);
+            }
+        }
+    }
+}
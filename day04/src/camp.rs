@@ -0,0 +1,236 @@
+//! Analysis of every elf's range across the whole camp, not only the pairs on one line.
+//!
+//! Elves are numbered by their position in the slice returned by [`elves`]. Everything is
+//! a sweep over all elves' ranges sorted by start or over their start and end events, so
+//! it runs in `O(n log n)` in the number of ranges, plus the size of the output.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::{Assignment, SectionRange, Sections};
+
+/// The sections of all elves, in line order.
+pub fn elves(assignments: &[Assignment]) -> Vec<Sections> {
+    assignments
+        .iter()
+        .flat_map(|a| a.elves.iter().cloned())
+        .collect()
+}
+
+/// Every range with the elf it belongs to, sorted by start.
+fn ranges(elves: &[Sections]) -> Vec<(SectionRange, usize)> {
+    let mut ranges = elves
+        .iter()
+        .enumerate()
+        .flat_map(|(i, e)| e.ranges().iter().map(move |&r| (r, i)))
+        .collect::<Vec<_>>();
+    ranges.sort_unstable_by_key(|(r, _)| r.start);
+    ranges
+}
+
+/// Every pair of elves `(i, j)` with `i < j` whose sections overlap.
+pub fn overlapping_pairs(elves: &[Sections]) -> Vec<(usize, usize)> {
+    let mut active = BinaryHeap::new();
+    let mut pairs = Vec::new();
+    for (range, i) in ranges(elves) {
+        while active
+            .peek()
+            .is_some_and(|&Reverse((end, _))| end < range.start)
+        {
+            active.pop();
+        }
+        for &Reverse((_, j)) in active.iter() {
+            pairs.push((i.min(j), i.max(j)));
+        }
+        active.push(Reverse((range.end, i)));
+    }
+    // Elves with several ranges can meet more than once.
+    pairs.sort_unstable();
+    pairs.dedup();
+    pairs
+}
+
+/// A maximal run of sections covered by the same number of elves.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    pub range: SectionRange,
+    pub elves: usize,
+}
+
+/// The covered sections as runs in increasing order. Sections no elf covers are left out.
+pub fn coverage(elves: &[Sections]) -> Vec<Coverage> {
+    let mut events = Vec::new();
+    for (r, _) in ranges(elves) {
+        events.push((r.start, 1isize));
+        // A range ending at `u64::MAX` never closes.
+        if let Some(after) = r.end.checked_add(1) {
+            events.push((after, -1));
+        }
+    }
+    events.sort_unstable();
+    let mut runs: Vec<Coverage> = Vec::new();
+    let mut depth = 0isize;
+    let mut idx = 0;
+    while idx < events.len() {
+        let at = events[idx].0;
+        let before = depth;
+        while idx < events.len() && events[idx].0 == at {
+            depth += events[idx].1;
+            idx += 1;
+        }
+        if depth == before {
+            continue;
+        }
+        if let Some(last) = runs.last_mut().filter(|_| before > 0) {
+            last.range.end = at - 1;
+        }
+        if depth > 0 {
+            runs.push(Coverage {
+                range: SectionRange { start: at, end: at },
+                elves: depth as usize,
+            });
+        }
+    }
+    if let Some(last) = runs.last_mut() {
+        if depth > 0 {
+            last.range.end = u64::MAX;
+        }
+    }
+    runs
+}
+
+/// The sections covered by at least `k` elves, as merged ranges.
+pub fn covered_by_at_least(elves: &[Sections], k: usize) -> Vec<SectionRange> {
+    let mut merged: Vec<SectionRange> = Vec::new();
+    for run in coverage(elves).into_iter().filter(|c| c.elves >= k) {
+        match merged.last_mut() {
+            Some(last) if last.end.checked_add(1) == Some(run.range.start) => {
+                last.end = run.range.end
+            }
+            _ => merged.push(run.range),
+        }
+    }
+    merged
+}
+
+/// The gaps no elf covers between the first and last covered section.
+pub fn uncovered(elves: &[Sections]) -> Vec<SectionRange> {
+    covered_by_at_least(elves, 1)
+        .windows(2)
+        .map(|w| SectionRange {
+            start: w[0].end + 1,
+            end: w[1].start - 1,
+        })
+        .collect()
+}
+
+/// A largest set of mutually overlapping elves and a section they all cover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clique {
+    pub section: u64,
+    pub elves: Vec<usize>,
+}
+
+/// The largest set of elves sharing one section. For elves with a single range this is
+/// the largest clique of mutually overlapping elves, since ranges that overlap pairwise
+/// share a common section.
+pub fn max_clique(elves: &[Sections]) -> Option<Clique> {
+    let deepest = coverage(elves).into_iter().max_by(|a, b| {
+        a.elves
+            .cmp(&b.elves)
+            .then(b.range.start.cmp(&a.range.start))
+    })?;
+    let section = deepest.range.start;
+    Some(Clique {
+        section,
+        elves: (0..elves.len())
+            .filter(|&i| elves[i].contains_section(section))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use common::rng::Rng;
+
+    use crate::camp::{
+        coverage, covered_by_at_least, elves, max_clique, overlapping_pairs, uncovered,
+    };
+    use crate::{read_assignments_p1, SectionRange, Sections};
+
+    fn range(start: u64, end: u64) -> SectionRange {
+        SectionRange::new(start, end).unwrap()
+    }
+
+    fn elf(start: u64, end: u64) -> Sections {
+        Sections::from(range(start, end))
+    }
+
+    #[test]
+    fn test_camp() {
+        let ranges = elves(&read_assignments_p1("src/test_input.txt").unwrap());
+        assert_eq!(12, ranges.len());
+        let counts = coverage(&ranges)
+            .iter()
+            .map(|c| (c.range.start, c.range.end, c.elves))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (2, 2, 4),
+                (3, 3, 5),
+                (4, 5, 7),
+                (6, 6, 8),
+                (7, 7, 6),
+                (8, 8, 4),
+                (9, 9, 1)
+            ],
+            counts
+        );
+        assert_eq!(vec![range(6, 6)], covered_by_at_least(&ranges, 8));
+        assert_eq!(vec![range(2, 8)], covered_by_at_least(&ranges, 4));
+        assert!(uncovered(&ranges).is_empty());
+        let clique = max_clique(&ranges).unwrap();
+        assert_eq!((6, 8), (clique.section, clique.elves.len()));
+
+        let sparse = [elf(1, 2), elf(5, 6), elf(10, 10), elf(2, 5)];
+        assert_eq!(vec![range(7, 9)], uncovered(&sparse));
+        assert_eq!(vec![(0, 3), (1, 3)], overlapping_pairs(&sparse));
+        assert!(max_clique(&[]).is_none());
+        assert_eq!(1, coverage(&[elf(3, u64::MAX)]).len());
+
+        let split = [Sections::new([range(1, 2), range(5, 6)]), elf(1, 9)];
+        assert_eq!(vec![(0, 1)], overlapping_pairs(&split));
+        assert_eq!(
+            vec![range(1, 2), range(5, 6)],
+            covered_by_at_least(&split, 2)
+        );
+    }
+
+    #[test]
+    fn test_overlapping_pairs_match_brute_force() {
+        let mut rng = Rng::new(43);
+        for _ in 0..50 {
+            let ranges = (0..20)
+                .map(|_| {
+                    let start = rng.below(30) as u64;
+                    elf(start, start + rng.below(6) as u64)
+                })
+                .collect::<Vec<_>>();
+            let mut expected = Vec::new();
+            for i in 0..ranges.len() {
+                for j in i + 1..ranges.len() {
+                    if ranges[i].overlaps(&ranges[j]) {
+                        expected.push((i, j));
+                    }
+                }
+            }
+            assert_eq!(expected, overlapping_pairs(&ranges));
+            let clique = max_clique(&ranges).unwrap();
+            let depth = (0..40)
+                .map(|s| ranges.iter().filter(|r| r.contains_section(s)).count())
+                .max()
+                .unwrap();
+            assert_eq!(depth, clique.elves.len());
+        }
+    }
+}
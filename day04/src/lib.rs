@@ -0,0 +1,336 @@
+//! Day 4: Camp Cleanup.
+//!
+//! Each line lists the inclusive section ranges of some elves, written `a-b,c-d`. An elf
+//! given several ranges has them separated by `;`, as in `2-4;7-9`, and an elf with no
+//! sections is written `-`.
+
+pub mod camp;
+pub mod reassign;
+
+use std::fmt::Display;
+
+use anyhow::{anyhow, Error, Result};
+
+/// An inclusive range of section IDs with `start <= end`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SectionRange {
+    start: u64,
+    end: u64,
+}
+
+impl SectionRange {
+    /// A range from `start` to `end`, or an error if `start > end`.
+    pub fn new(start: u64, end: u64) -> Result<Self> {
+        if start <= end {
+            Ok(Self { start, end })
+        } else {
+            Err(anyhow!("Range start must be less than or equal to the end"))
+        }
+    }
+    /// The first section in the range.
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+    /// The last section in the range.
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+}
+
+impl Display for SectionRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl TryFrom<&str> for SectionRange {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (start, end) = value
+            .split_once('-')
+            .ok_or_else(|| anyhow!("Could not parse end of range"))?;
+        if end.contains('-') {
+            return Err(anyhow!("Range `{}` has more than two bounds", value));
+        }
+        if end.is_empty() {
+            return Err(anyhow!(
+                "Range `{}` has no end; open ranges are not supported",
+                value
+            ));
+        }
+        let start = start
+            .parse::<u64>()
+            .map_err(|_e| anyhow!("Could not convert start of range to `u64`"))?;
+        let end = end
+            .parse::<u64>()
+            .map_err(|_e| anyhow!("Could not convert end of range to `u64`"))?;
+        Self::new(start, end)
+            .map_err(|_e| anyhow!("Range start must be less than or equal to the end"))
+    }
+}
+
+/// The sections given to one elf, kept as sorted ranges with no two overlapping or
+/// adjacent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sections(Vec<SectionRange>);
+
+impl Sections {
+    /// The union of `ranges`.
+    pub fn new(ranges: impl IntoIterator<Item = SectionRange>) -> Self {
+        let mut sorted = ranges.into_iter().collect::<Vec<SectionRange>>();
+        sorted.sort_unstable_by_key(|r| r.start);
+        let mut merged: Vec<SectionRange> = Vec::with_capacity(sorted.len());
+        for r in sorted {
+            match merged.last_mut() {
+                Some(last) if r.start <= last.end.saturating_add(1) => {
+                    last.end = last.end.max(r.end)
+                }
+                _ => merged.push(r),
+            }
+        }
+        Self(merged)
+    }
+
+    /// The ranges in increasing order.
+    pub fn ranges(&self) -> &[SectionRange] {
+        &self.0
+    }
+
+    /// The number of sections.
+    pub fn len(&self) -> u64 {
+        self.0.iter().map(|r| r.end - r.start + 1).sum()
+    }
+
+    /// Whether there are no sections.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether section `s` is included.
+    pub fn contains_section(&self, s: u64) -> bool {
+        let idx = self.0.partition_point(|r| r.start <= s);
+        idx > 0 && s <= self.0[idx - 1].end
+    }
+
+    /// Whether every section of `other` is included.
+    pub fn contains(&self, other: &Sections) -> bool {
+        other.0.iter().all(|r| {
+            let idx = self.0.partition_point(|o| o.start <= r.start);
+            idx > 0 && r.end <= self.0[idx - 1].end
+        })
+    }
+
+    /// Whether the two share at least one section.
+    pub fn overlaps(&self, other: &Sections) -> bool {
+        let (mut i, mut j) = (0, 0);
+        while i < self.0.len() && j < other.0.len() {
+            let (a, b) = (self.0[i], other.0[j]);
+            if a.start <= b.end && b.start <= a.end {
+                return true;
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        false
+    }
+}
+
+impl From<SectionRange> for Sections {
+    fn from(range: SectionRange) -> Self {
+        Self(vec![range])
+    }
+}
+
+impl Display for Sections {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "-");
+        }
+        for (idx, r) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ";")?;
+            }
+            write!(f, "{}", r)?;
+        }
+        Ok(())
+    }
+}
+
+/// A relation between two elves' sections. An elf with no sections is contained in every
+/// other elf and overlaps none.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Predicate {
+    /// One elf's sections include all of the other's.
+    Contains,
+    /// The elves share a section.
+    Overlaps,
+}
+
+impl Predicate {
+    /// Whether the relation holds between `a` and `b`, in either direction.
+    pub fn holds(self, a: &Sections, b: &Sections) -> bool {
+        match self {
+            Predicate::Contains => a.contains(b) || b.contains(a),
+            Predicate::Overlaps => a.overlaps(b),
+        }
+    }
+}
+
+/// Which pairs of elves on a line must satisfy a [`Predicate`]. A line with fewer than two
+/// elves has no pairs, so `Any` is false for it and `All` is true.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Pairs {
+    Any,
+    All,
+}
+
+/// The sections given to the elves on one line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    pub elves: Vec<Sections>,
+}
+
+impl Assignment {
+    /// Whether `predicate` holds for any or all pairs of elves.
+    pub fn check(&self, pairs: Pairs, predicate: Predicate) -> bool {
+        let mut results = self.elves.iter().enumerate().flat_map(|(i, a)| {
+            self.elves[i + 1..]
+                .iter()
+                .map(move |b| predicate.holds(a, b))
+        });
+        match pairs {
+            Pairs::Any => results.any(|r| r),
+            Pairs::All => results.all(|r| r),
+        }
+    }
+
+    /// Whether one elf's sections fully contain another's.
+    pub fn containment(&self) -> bool {
+        self.check(Pairs::Any, Predicate::Contains)
+    }
+
+    /// Whether two elves share at least one section.
+    pub fn overlap(&self) -> bool {
+        self.check(Pairs::Any, Predicate::Overlaps)
+    }
+}
+
+impl Display for Assignment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, elf) in self.elves.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", elf)?;
+        }
+        Ok(())
+    }
+}
+
+/// Read `input` as a list of assignments.
+pub fn read_assignments_p1(input: &str) -> Result<Vec<Assignment>> {
+    parse_assignments_p1(&std::fs::read_to_string(input)?)
+}
+
+/// Parse one assignment per line: comma-separated elves, each one or more `a-b` ranges
+/// separated by `;`, or `-` for none.
+pub fn parse_assignments_p1(data: &str) -> Result<Vec<Assignment>> {
+    let mut assignments = Vec::new();
+    for (idx, line) in data.lines().enumerate() {
+        let elves = line
+            .split(',')
+            .map(|elf| {
+                if elf == "-" {
+                    return Ok(Sections::default());
+                }
+                Ok(Sections::new(
+                    elf.split(';')
+                        .map(SectionRange::try_from)
+                        .collect::<Result<Vec<SectionRange>>>()?,
+                ))
+            })
+            .collect::<Result<Vec<Sections>>>()
+            .map_err(|e| anyhow!("Line {}: {}", idx + 1, e))?;
+        assignments.push(Assignment { elves });
+    }
+    Ok(assignments)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fuzz::check_parser;
+
+    use crate::{parse_assignments_p1, read_assignments_p1, Pairs, Predicate};
+
+    #[test]
+    fn test_part_one() {
+        assert_eq!(
+            2,
+            read_assignments_p1("src/test_input.txt")
+                .unwrap()
+                .iter()
+                .map(|a| a.containment())
+                .filter(|b| *b)
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_part_two() {
+        assert_eq!(
+            4,
+            read_assignments_p1("src/test_input.txt")
+                .unwrap()
+                .iter()
+                .map(|a| a.overlap())
+                .filter(|b| *b)
+                .count()
+        )
+    }
+
+    #[test]
+    fn test_multi_range() {
+        let lines =
+            parse_assignments_p1("2-4;7-9,3-3;8-8,1-1\n1-5,2-3,3-4\n1-2;3-4,6-6;5-5").unwrap();
+        assert_eq!(3, lines[0].elves.len());
+        assert!(lines[0].containment());
+        assert!(lines[0].check(Pairs::Any, Predicate::Overlaps));
+        assert!(!lines[0].check(Pairs::All, Predicate::Overlaps));
+        assert!(lines[1].check(Pairs::All, Predicate::Overlaps));
+        assert!(!lines[1].check(Pairs::All, Predicate::Contains));
+        assert_eq!("1-4,5-6", lines[2].to_string());
+        assert_eq!(6, lines[0].elves[0].len());
+        assert!(parse_assignments_p1("1-2;,3-4").is_err());
+        assert!(parse_assignments_p1("4-2,3-4").is_err());
+        assert!(parse_assignments_p1("1-2-3,3-4").is_err());
+        assert!(parse_assignments_p1("5-,3-4").is_err());
+    }
+
+    #[test]
+    fn test_vacuous_pairs() {
+        let lines = parse_assignments_p1("2-4\n-,3-4\n-,-").unwrap();
+        for predicate in [Predicate::Contains, Predicate::Overlaps] {
+            assert!(lines[0].check(Pairs::All, predicate));
+            assert!(!lines[0].check(Pairs::Any, predicate));
+        }
+        assert!(lines[1].check(Pairs::All, Predicate::Contains));
+        assert!(!lines[1].check(Pairs::Any, Predicate::Overlaps));
+        assert!(lines[2].containment());
+        assert!(!lines[2].overlap());
+    }
+
+    #[test]
+    fn test_fuzz_parsers() {
+        let base = std::fs::read_to_string("src/test_input.txt").unwrap();
+        check_parser(
+            "parse_assignments_p1",
+            &base,
+            4,
+            2_000,
+            parse_assignments_p1,
+        );
+    }
+}
@@ -1,126 +1,77 @@
-use anyhow::{anyhow, Error, Result};
+use anyhow::{anyhow, Result};
+use common::profile::measure;
+use day4::camp::{covered_by_at_least, elves, max_clique, overlapping_pairs, uncovered};
+use day4::reassign::plan;
+use day4::{read_assignments_p1, Pairs, Predicate};
 
-struct SectionRange {
-    start: u64,
-    end: u64,
-}
-
-impl SectionRange {
-    fn new(start: u64, end: u64) -> Result<Self> {
-        if start <= end {
-            Ok(Self { start, end })
-        } else {
-            Err(anyhow!("Range start must be less than or equal to the end"))
+fn main() -> Result<()> {
+    let args = std::env::args().collect::<Vec<String>>();
+    let assignments = measure("parse", || read_assignments_p1("src/input.txt"))?;
+    if args.iter().any(|a| a == "--camp") {
+        let min_elves = match args.iter().position(|a| a == "--min-elves") {
+            Some(i) => args
+                .get(i + 1)
+                .ok_or_else(|| anyhow!("`--min-elves` expects a value"))?
+                .parse()?,
+            None => 2,
+        };
+        let ranges = elves(&assignments);
+        let pairs = measure("overlapping pairs", || overlapping_pairs(&ranges));
+        println!("Overlapping pairs of elves: {}", pairs.len());
+        let covered = measure("coverage", || covered_by_at_least(&ranges, min_elves));
+        let sections = covered.iter().map(|r| r.end() - r.start() + 1).sum::<u64>();
+        println!("Sections covered by {min_elves} or more elves: {sections}");
+        for range in covered {
+            println!("  {}-{}", range.start(), range.end());
         }
+        println!("Uncovered sections:");
+        for range in uncovered(&ranges) {
+            println!("  {}-{}", range.start(), range.end());
+        }
+        if let Some(clique) = max_clique(&ranges) {
+            println!(
+                "Largest clique: {} elves sharing section {}",
+                clique.elves.len(),
+                clique.section
+            );
+        }
+        return Ok(());
     }
-}
-
-impl TryFrom<&str> for SectionRange {
-    type Error = Error;
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let mut s = value.split('-');
-        let start = s
-            .next()
-            .ok_or_else(|| anyhow!("Could not parse start of range"))?
-            .parse::<u64>()
-            .map_err(|_e| anyhow!("Could not convert start of range to `u64`"))?;
-        let end = s
-            .next()
-            .ok_or_else(|| anyhow!("Could not parse end of range"))?
-            .parse::<u64>()
-            .map_err(|_e| anyhow!("Could not convert end of range to `u64`"))?;
-        Self::new(start, end)
-            .map_err(|_e| anyhow!("Range start must be less than or equal to the end"))
-    }
-}
-
-struct Assignment {
-    elf1: SectionRange,
-    elf2: SectionRange,
-}
-
-impl Assignment {
-    fn containment(&self) -> bool {
-        matches!((
-            self.elf1.start,
-            self.elf1.end,
-            self.elf2.start,
-            self.elf2.end,
-        ), (s1, e1, s2, e2) if (s1 >= s2 && e1 <= e2) || (s1 <= s2 && e1 >= e2))
-    }
-
-    fn overlap(&self) -> bool {
-        matches!((
-            self.elf1.start,
-            self.elf1.end,
-            self.elf2.start,
-            self.elf2.end,
-        ), (s1, e1, s2, e2) if (s1 <= s2 && e1 >= s2) || (s2 <= s1 && e2 >= s1))
-    }
-}
 
-fn read_assignments_p1(input: &str) -> Result<Vec<Assignment>> {
-    let mut assignments = Vec::new();
-    for line in std::fs::read_to_string(input)?.lines() {
-        let mut s = line.split(',');
-        let e1 = SectionRange::try_from(
-            s.next()
-                .ok_or_else(|| anyhow!("Could not parse first elf assignment"))?,
-        )?;
-        let e2 = SectionRange::try_from(
-            s.next()
-                .ok_or_else(|| anyhow!("Could not parse second elf assignment"))?,
-        )?;
-        assignments.push(Assignment { elf1: e1, elf2: e2 });
+    if args.iter().any(|a| a == "--reassign") {
+        let plan = measure("reassign", || plan(&assignments));
+        for unplanned in &plan.unplanned {
+            eprintln!("kept {}", unplanned);
+        }
+        for assignment in &plan.assignments {
+            println!("{}", assignment);
+        }
+        println!("Total distance: {}", plan.total_distance);
+        return Ok(());
     }
-    Ok(assignments)
-}
 
-fn main() {
-    let fully_contained_count = read_assignments_p1("src/input.txt")
-        .unwrap()
-        .iter()
-        .map(|a| a.containment())
-        .filter(|b| *b)
-        .count();
+    let pairs = if args.iter().any(|a| a == "--all-pairs") {
+        Pairs::All
+    } else {
+        Pairs::Any
+    };
+    let fully_contained_count = measure("part one", || {
+        assignments
+            .iter()
+            .map(|a| a.check(pairs, Predicate::Contains))
+            .filter(|b| *b)
+            .count()
+    });
     println!("Part one: {fully_contained_count}");
 
-    let overlap_count = read_assignments_p1("src/input.txt")
-        .unwrap()
-        .iter()
-        .map(|a| a.overlap())
-        .filter(|b| *b)
-        .count();
+    let overlap_count = measure("part two", || {
+        assignments
+            .iter()
+            .map(|a| a.check(pairs, Predicate::Overlaps))
+            .filter(|b| *b)
+            .count()
+    });
     println!("Part two: {overlap_count}");
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::read_assignments_p1;
 
-    #[test]
-    fn test_part_one() {
-        assert_eq!(
-            2,
-            read_assignments_p1("src/test_input.txt")
-                .unwrap()
-                .iter()
-                .map(|a| a.containment())
-                .filter(|b| *b)
-                .count()
-        );
-    }
-
-    #[test]
-    fn test_part_two() {
-        assert_eq!(
-            4,
-            read_assignments_p1("src/test_input.txt")
-                .unwrap()
-                .iter()
-                .map(|a| a.overlap())
-                .filter(|b| *b)
-                .count()
-        )
-    }
+    Ok(())
 }
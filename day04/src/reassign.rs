@@ -0,0 +1,257 @@
+//! New assignments that split each line's sections between its elves without overlap.
+//!
+//! For every line, [`reassign`] gives each elf one contiguous range so that the ranges
+//! cover exactly the union of the line's old sections and no two share a section. The
+//! distance of an elf is how far its start and end move from those of its old sections,
+//! and the total over the line is minimized. An elf whose work the others already cover,
+//! such as one duplicating or contained in another, can instead be released with no
+//! sections (written `-`); that costs the length of its old span, as if both ends moved
+//! onto each other and then off the last section. The search runs over the union's
+//! sections from left to right, tracking which elves already have a range, so it is exact
+//! but grows as `2^elves * sections^2`; lines past [`SEARCH_LIMIT`] are reported instead.
+
+use std::fmt::Display;
+
+use anyhow::{anyhow, Result};
+
+use crate::{Assignment, SectionRange, Sections};
+
+/// The largest `2^elves * sections^2` search [`reassign`] will run for one line.
+pub const SEARCH_LIMIT: u64 = 50_000_000;
+
+/// A line's new assignment and the distance its elves moved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reassignment {
+    pub assignment: Assignment,
+    pub distance: u64,
+}
+
+/// The closest assignment of `assignment`'s sections with no overlap and at most one
+/// contiguous range per elf, or an error if there is none or the search is too large.
+pub fn reassign(assignment: &Assignment) -> Result<Reassignment> {
+    let elves = &assignment.elves;
+    // Each elf's first and last old section, or `None` for an elf with no sections.
+    let hulls = elves
+        .iter()
+        .map(|e| Some((e.ranges().first()?.start, e.ranges().last()?.end)))
+        .collect::<Vec<Option<(u64, u64)>>>();
+    let release_cost = |elf: usize| hulls[elf].map_or(0, |(start, end)| end - start + 1);
+    let union = Sections::new(elves.iter().flat_map(|e| e.ranges().iter().copied()));
+    let size = union.len();
+    if elves.len() >= 32
+        || (1u64 << elves.len())
+            .checked_mul(size)
+            .and_then(|s| s.checked_mul(size))
+            .is_none_or(|s| s > SEARCH_LIMIT)
+    {
+        return Err(anyhow!(
+            "needs a search larger than {} to split {} sections between {} elves",
+            SEARCH_LIMIT,
+            size,
+            elves.len()
+        ));
+    }
+
+    // The union's sections in order, with the index of the last section of each one's run.
+    let mut sections = Vec::with_capacity(size as usize);
+    let mut run_end = Vec::with_capacity(size as usize);
+    for r in union.ranges() {
+        let last = sections.len() + (r.end - r.start) as usize;
+        sections.extend(r.start..=r.end);
+        run_end.resize(sections.len(), last);
+    }
+
+    // best[mask][i]: the least distance for the elves in `mask` to cover sections `..i`.
+    // from[mask][i] is the elf covering the last of them and the index its range starts.
+    let width = sections.len() + 1;
+    let full = (1usize << elves.len()) - 1;
+    let mut best = vec![None::<u64>; (full + 1) * width];
+    let mut from = vec![(0usize, 0usize); (full + 1) * width];
+    best[0] = Some(0);
+    for i in 0..sections.len() {
+        for mask in 0..full {
+            let Some(cost) = best[mask * width + i] else {
+                continue;
+            };
+            for (elf, hull) in hulls.iter().enumerate() {
+                let Some((start, end)) = *hull else { continue };
+                if mask & (1 << elf) != 0 {
+                    continue;
+                }
+                let after = mask | (1 << elf);
+                for j in i..=run_end[i] {
+                    let cost = cost + sections[i].abs_diff(start) + sections[j].abs_diff(end);
+                    let state = after * width + j + 1;
+                    if best[state].is_none_or(|c| cost < c) {
+                        best[state] = Some(cost);
+                        from[state] = (elf, i);
+                    }
+                }
+            }
+        }
+    }
+    // The elves outside the mask that covers every section are released.
+    let (distance, mut mask) = (0..=full)
+        .filter_map(|mask| {
+            let released = (0..elves.len())
+                .filter(|elf| mask & (1 << elf) == 0)
+                .map(release_cost)
+                .sum::<u64>();
+            Some((best[mask * width + sections.len()]? + released, mask))
+        })
+        .min()
+        .ok_or_else(|| anyhow!("cannot be split into contiguous ranges"))?;
+
+    let mut new = vec![Sections::default(); elves.len()];
+    let mut i = sections.len();
+    while mask != 0 {
+        let (elf, start) = from[mask * width + i];
+        new[elf] = Sections::from(SectionRange {
+            start: sections[start],
+            end: sections[i - 1],
+        });
+        mask &= !(1 << elf);
+        i = start;
+    }
+    Ok(Reassignment {
+        assignment: Assignment { elves: new },
+        distance,
+    })
+}
+
+/// A line [`plan`] left unchanged, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unplanned {
+    pub line: usize,
+    pub assignment: Assignment,
+    pub reason: String,
+}
+
+impl Display for Unplanned {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {} {}", self.line, self.assignment, self.reason)
+    }
+}
+
+/// New assignments for a whole list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    /// One assignment per input line; unplanned lines are kept as they were.
+    pub assignments: Vec<Assignment>,
+    pub total_distance: u64,
+    pub unplanned: Vec<Unplanned>,
+}
+
+/// Reassign every line of `assignments`.
+pub fn plan(assignments: &[Assignment]) -> Plan {
+    let mut plan = Plan {
+        assignments: Vec::with_capacity(assignments.len()),
+        total_distance: 0,
+        unplanned: Vec::new(),
+    };
+    for (idx, assignment) in assignments.iter().enumerate() {
+        match reassign(assignment) {
+            Ok(r) => {
+                plan.total_distance += r.distance;
+                plan.assignments.push(r.assignment);
+            }
+            Err(e) => {
+                plan.unplanned.push(Unplanned {
+                    line: idx + 1,
+                    assignment: assignment.clone(),
+                    reason: e.to_string(),
+                });
+                plan.assignments.push(assignment.clone());
+            }
+        }
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reassign::{plan, reassign};
+    use crate::{
+        parse_assignments_p1, read_assignments_p1, Assignment, Pairs, Predicate, Sections,
+    };
+
+    #[test]
+    fn test_reassign() {
+        let assignments = read_assignments_p1("src/test_input.txt").unwrap();
+        let plan = plan(&assignments);
+        assert!(plan.unplanned.is_empty());
+        for (old, new) in assignments.iter().zip(&plan.assignments) {
+            let union = |a: &Assignment| {
+                Sections::new(a.elves.iter().flat_map(|e| e.ranges().iter().copied()))
+            };
+            assert_eq!(union(old), union(new));
+            assert!(!new.overlap());
+            assert!(new.elves.iter().all(|e| e.ranges().len() <= 1));
+        }
+        let output = plan
+            .assignments
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!("2-4,6-8", output[0]);
+        assert_eq!(
+            parse_assignments_p1(&output.join("\n")).unwrap(),
+            plan.assignments
+        );
+
+        let nested = &parse_assignments_p1("2-8,3-7").unwrap()[0];
+        let r = reassign(nested).unwrap();
+        assert_eq!(
+            ("2-8,-".to_string(), 5),
+            (r.assignment.to_string(), r.distance)
+        );
+        let split = &parse_assignments_p1("1-3;5-6,1-1").unwrap()[0];
+        let r = reassign(split).unwrap();
+        assert_eq!(
+            ("5-6,1-3".to_string(), 6),
+            (r.assignment.to_string(), r.distance)
+        );
+    }
+
+    #[test]
+    fn test_release() {
+        let cases = [
+            ("56-56,56-56", "56-56,-", 1),
+            ("4-6,1-9", "-,1-9", 3),
+            ("1-9,4-5", "1-9,-", 2),
+            ("-,2-3", "-,2-3", 0),
+        ];
+        for (line, expected, distance) in cases {
+            let assignment = &parse_assignments_p1(line).unwrap()[0];
+            let r = reassign(assignment).unwrap();
+            assert_eq!(
+                (expected.to_string(), distance),
+                (r.assignment.to_string(), r.distance),
+                "{line}"
+            );
+            assert!(!r.assignment.overlap());
+            assert_eq!(
+                vec![r.assignment.clone()],
+                parse_assignments_p1(expected).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_unplanned() {
+        let assignments = parse_assignments_p1("56-56,56-56\n1-2;5-6;9-9,3-3\n1-4,3-6").unwrap();
+        let plan = plan(&assignments);
+        assert_eq!(
+            vec![2],
+            plan.unplanned.iter().map(|u| u.line).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "line 2: 1-2;5-6;9-9,3-3 cannot be split into contiguous ranges",
+            plan.unplanned[0].to_string()
+        );
+        assert_eq!("56-56,-", plan.assignments[0].to_string());
+        assert_eq!(assignments[1], plan.assignments[1]);
+        assert!(!plan.assignments[2].check(Pairs::Any, Predicate::Overlaps));
+        assert_eq!(3, plan.total_distance);
+    }
+}
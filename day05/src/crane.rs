@@ -0,0 +1,323 @@
+//! Crane models: how the crates of one [`Move`] get from one stack to another.
+//!
+//! Every model lifts crates off the source stack and sets them on top of the destination.
+//! They differ in how many crates one lift holds, which crates it takes, and in what
+//! order they come down. Every model checks that a move can be carried out in full before
+//! touching the stacks, so a failed move leaves them as they were. [`crane_by_name`] picks
+//! a model from a command-line name.
+
+use anyhow::{anyhow, Result};
+
+use crate::executor::Executor;
+use crate::{stack_index, top_message, Move, Stack};
+
+/// A crane model.
+pub trait Crane {
+    /// The model's command-line name.
+    fn name(&self) -> String;
+    /// Carry out `m` on `stacks`, or leave them unchanged and return an error if it cannot
+    /// be carried out in full.
+    fn execute(&self, m: &Move, stacks: &mut [Stack]) -> Result<()>;
+}
+
+/// The indices of `m`'s source and destination stacks if `m` can be carried out on
+/// `stacks`, or why it can't. Both stacks must exist and differ, and the source must hold
+/// at least `m.num` crates.
+pub(crate) fn validate(m: &Move, stacks: &[Stack]) -> Result<(usize, usize), String> {
+    let from = stack_index(stacks, m.from)
+        .ok_or_else(|| format!("has no stack {} to take from", m.from))?;
+    let to = stack_index(stacks, m.to).ok_or_else(|| format!("has no stack {} to put on", m.to))?;
+    if from == to {
+        return Err("takes from and puts on the same stack".to_string());
+    }
+    let height = stacks[from].len();
+    if m.num > height {
+        return Err(format!(
+            "takes {} crates from stack {} holding {}",
+            m.num, m.from, height
+        ));
+    }
+    Ok((from, to))
+}
+
+/// [`validate`] as an error naming the move.
+fn check(m: &Move, stacks: &[Stack]) -> Result<(usize, usize)> {
+    validate(m, stacks).map_err(|reason| anyhow!("{} {}", m, reason))
+}
+
+/// Take the top `num` crates off stack `from`, bottom first.
+fn lift(stacks: &mut [Stack], from: usize, num: usize) -> Result<Vec<String>> {
+    stacks[from]
+        .take_top(num)
+        .ok_or_else(|| anyhow!("Can't pop an empty stack!"))
+}
+
+/// The CrateMover 9000 moves crates one at a time, reversing their order.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "9000".to_string()
+    }
+    fn execute(&self, m: &Move, stacks: &mut [Stack]) -> Result<()> {
+        let (from, to) = check(m, stacks)?;
+        let crates = lift(stacks, from, m.num)?;
+        stacks[to].put(crates.into_iter().rev());
+        Ok(())
+    }
+}
+
+/// The CrateMover 9001 moves all crates at once, keeping their order.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "9001".to_string()
+    }
+    fn execute(&self, m: &Move, stacks: &mut [Stack]) -> Result<()> {
+        let (from, to) = check(m, stacks)?;
+        let crates = lift(stacks, from, m.num)?;
+        stacks[to].put(crates);
+        Ok(())
+    }
+}
+
+/// Lifts at most `capacity` crates at a time, keeping the order within each lift.
+#[derive(Debug, Copy, Clone)]
+pub struct Capacity {
+    capacity: usize,
+}
+
+impl Capacity {
+    /// A crane lifting up to `capacity` crates, which must be at least 1.
+    pub fn new(capacity: usize) -> Result<Self> {
+        if capacity == 0 {
+            return Err(anyhow!("Crane capacity must be at least 1"));
+        }
+        Ok(Self { capacity })
+    }
+}
+
+impl Crane for Capacity {
+    fn name(&self) -> String {
+        format!("capacity-{}", self.capacity)
+    }
+    fn execute(&self, m: &Move, stacks: &mut [Stack]) -> Result<()> {
+        let (from, to) = check(m, stacks)?;
+        let mut left = m.num;
+        while left > 0 {
+            let crates = lift(stacks, from, left.min(self.capacity))?;
+            left -= crates.len();
+            stacks[to].put(crates);
+        }
+        Ok(())
+    }
+}
+
+/// Pulls crates out from the bottom of the source stack as one block.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Bottom;
+
+impl Crane for Bottom {
+    fn name(&self) -> String {
+        "bottom".to_string()
+    }
+    fn execute(&self, m: &Move, stacks: &mut [Stack]) -> Result<()> {
+        let (from, to) = check(m, stacks)?;
+        let crates = stacks[from]
+            .take_bottom(m.num)
+            .ok_or_else(|| anyhow!("Can't pop an empty stack!"))?;
+        stacks[to].put(crates);
+        Ok(())
+    }
+}
+
+/// Has two arms taking turns, one crate each: the first arm takes from the top of the
+/// source stack and the second from its bottom, starting with the first.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct TwoArm;
+
+impl Crane for TwoArm {
+    fn name(&self) -> String {
+        "two-arm".to_string()
+    }
+    fn execute(&self, m: &Move, stacks: &mut [Stack]) -> Result<()> {
+        let (from, to) = check(m, stacks)?;
+        for turn in 0..m.num {
+            let crates = match turn % 2 {
+                0 => stacks[from].take_top(1),
+                _ => stacks[from].take_bottom(1),
+            }
+            .ok_or_else(|| anyhow!("Can't pop an empty stack!"))?;
+            stacks[to].put(crates);
+        }
+        Ok(())
+    }
+}
+
+/// The crane named `name`: `9000`, `9001`, `capacity-K`, `bottom` or `two-arm`.
+pub fn crane_by_name(name: &str) -> Result<Box<dyn Crane>> {
+    match name {
+        "9000" => Ok(Box::new(CrateMover9000)),
+        "9001" => Ok(Box::new(CrateMover9001)),
+        "bottom" => Ok(Box::new(Bottom)),
+        "two-arm" => Ok(Box::new(TwoArm)),
+        _ => match name.strip_prefix("capacity-") {
+            Some(k) => Ok(Box::new(Capacity::new(k.parse()?)?)),
+            None => Err(anyhow!(
+                "Unknown crane `{}`, expected `9000`, `9001`, `capacity-K`, `bottom` or `two-arm`",
+                name
+            )),
+        },
+    }
+}
+
+/// Carry out `moves` on `stacks` with `crane` and return the top-of-stack message. The
+/// moves go through an [`Executor`] without history, so a bad one is reported with its
+/// line, counting from `first_line`, and the stack heights; `stacks` are only changed if
+/// every move succeeds.
+pub fn run(
+    crane: &dyn Crane,
+    stacks: &mut [Stack],
+    moves: &[Move],
+    first_line: usize,
+) -> Result<String> {
+    let mut executor = Executor::new(crane, stacks.to_vec(), first_line);
+    for m in moves {
+        executor.apply(m)?;
+    }
+    for (stack, after) in stacks.iter_mut().zip(executor.into_stacks()) {
+        *stack = after;
+    }
+    Ok(top_message(stacks))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crane::{crane_by_name, run};
+    use crate::{read_stacks_and_moves, Move, Stack};
+
+    fn stacks(crates: &[&str]) -> Vec<Stack> {
+        crates
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let mut stack = Stack::new(i + 1);
+                stack.put(s.chars().map(String::from));
+                stack
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cranes() {
+        let m = Move {
+            num: 3,
+            from: 1,
+            to: 2,
+        };
+        for (name, expected) in [
+            ("9000", "XEDC"),
+            ("9001", "XCDE"),
+            ("capacity-2", "XDEC"),
+            ("bottom", "XABC"),
+            ("two-arm", "XEAD"),
+        ] {
+            let crane = crane_by_name(name).unwrap();
+            let mut s = stacks(&["ABCDE", "X"]);
+            crane.execute(&m, &mut s).unwrap();
+            let after = s[1].crates().concat();
+            assert_eq!(expected, after, "{}", crane.name());
+        }
+        assert!(crane_by_name("capacity-0").is_err());
+        assert!(crane_by_name("9002").is_err());
+    }
+
+    #[test]
+    fn test_failed_move_leaves_stacks() {
+        let bad_moves = [(6, 1, 2), (2, 3, 1), (3, 1, 3), (1, 2, 1)];
+        for name in ["9000", "9001", "capacity-2", "bottom", "two-arm"] {
+            let crane = crane_by_name(name).unwrap();
+            for (num, from, to) in bad_moves {
+                let mut s = stacks(&["ABCDE", ""]);
+                let m = Move { num, from, to };
+                assert!(crane.execute(&m, &mut s).is_err(), "{} {}", name, m);
+                assert_eq!(stacks(&["ABCDE", ""]), s, "{} {}", name, m);
+            }
+        }
+    }
+
+    #[test]
+    fn test_same_stack() {
+        let m = Move {
+            num: 2,
+            from: 1,
+            to: 1,
+        };
+        for name in ["9000", "9001", "capacity-1", "bottom", "two-arm"] {
+            let crane = crane_by_name(name).unwrap();
+            let mut s = stacks(&["ABCDE", "X"]);
+            let e = crane.execute(&m, &mut s).unwrap_err();
+            assert_eq!(
+                "move 2 from 1 to 1 takes from and puts on the same stack",
+                e.to_string(),
+                "{}",
+                name
+            );
+            assert_eq!(stacks(&["ABCDE", "X"]), s, "{}", name);
+            let e = run(crane.as_ref(), &mut s, &[m], 6).unwrap_err();
+            assert_eq!(
+                "line 6: move 2 from 1 to 1 takes from and puts on the same stack (heights 1:5 2:1)",
+                e.to_string(),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_same_message_for_all() {
+        let (initial, moves) = read_stacks_and_moves("src/test_input.txt").unwrap();
+        for (name, expected) in [
+            ("9000", "CMZ"),
+            ("capacity-1", "CMZ"),
+            ("9001", "MCD"),
+            ("capacity-3", "MCD"),
+        ] {
+            let crane = crane_by_name(name).unwrap();
+            let mut stacks = initial.clone();
+            assert_eq!(
+                expected,
+                run(crane.as_ref(), &mut stacks, &moves, 6).unwrap()
+            );
+        }
+        let mut stacks = initial.clone();
+        assert!(run(
+            crane_by_name("bottom").unwrap().as_ref(),
+            &mut stacks,
+            &moves,
+            6
+        )
+        .is_ok());
+        let mut stacks = initial.clone();
+        let bad = Move {
+            num: 1,
+            from: 4,
+            to: 1,
+        };
+        let e = run(
+            crane_by_name("9000").unwrap().as_ref(),
+            &mut stacks,
+            &[moves[0], bad],
+            6,
+        )
+        .unwrap_err();
+        assert_eq!(
+            "line 7: move 1 from 4 to 1 has no stack 4 to take from (heights 1:3 2:2 3:1)",
+            e.to_string()
+        );
+        assert_eq!(initial, stacks);
+    }
+}
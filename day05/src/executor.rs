@@ -0,0 +1,232 @@
+//! Carrying out moves one at a time with validation and an optional undo/redo history.
+//!
+//! [`Executor::apply`] checks a move against the current stacks before handing it to the
+//! crane, so a bad move is reported with its line and the stack heights instead of
+//! failing halfway through. With [`Executor::with_history`], each applied move records the
+//! two stacks it touched, which is enough to undo it whatever the crane model.
+
+use std::fmt::Display;
+
+use anyhow::{anyhow, Result};
+
+use crate::crane::{validate, Crane};
+use crate::{stack_index, Move, Stack};
+
+/// A move that cannot be carried out, with the state it was checked against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMove {
+    /// The 1-based line of the move in the input.
+    pub line: usize,
+    pub m: Move,
+    pub reason: String,
+    /// Each stack's label and height before the move.
+    pub heights: Vec<(usize, usize)>,
+}
+
+impl Display for InvalidMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {} {} (heights", self.line, self.m, self.reason)?;
+        for (label, height) in self.heights.iter() {
+            write!(f, " {}:{}", label, height)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// An applied move and the stacks it changed, as they were before.
+#[derive(Debug, Clone)]
+struct Applied {
+    m: Move,
+    before: Vec<(usize, Stack)>,
+}
+
+/// Stacks that moves are applied to one at a time.
+pub struct Executor<'a> {
+    crane: &'a dyn Crane,
+    stacks: Vec<Stack>,
+    first_line: usize,
+    step: usize,
+    history: bool,
+    done: Vec<Applied>,
+    undone: Vec<Move>,
+}
+
+impl<'a> Executor<'a> {
+    /// Start from `stacks`, with the first move on line `first_line` of the input and
+    /// each later move on the next line. No history is kept.
+    pub fn new(crane: &'a dyn Crane, stacks: Vec<Stack>, first_line: usize) -> Self {
+        Self {
+            crane,
+            stacks,
+            first_line,
+            step: 0,
+            history: false,
+            done: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// Keep a history of the moves applied from now on, so they can be undone.
+    pub fn with_history(mut self) -> Self {
+        self.history = true;
+        self
+    }
+
+    /// The current stacks.
+    pub fn stacks(&self) -> &[Stack] {
+        &self.stacks
+    }
+
+    /// Stop and return the current stacks.
+    pub fn into_stacks(self) -> Vec<Stack> {
+        self.stacks
+    }
+
+    /// The number of moves applied and not undone.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Whether `m` can be applied as the next move.
+    pub fn validate(&self, m: &Move) -> Result<(), InvalidMove> {
+        validate(m, &self.stacks)
+            .map(|_| ())
+            .map_err(|reason| InvalidMove {
+                line: self.first_line + self.step,
+                m: *m,
+                reason,
+                heights: self.stacks.iter().map(|s| (s.label(), s.len())).collect(),
+            })
+    }
+
+    /// Validate and apply `m`. This discards any undone moves.
+    pub fn apply(&mut self, m: &Move) -> Result<()> {
+        self.validate(m).map_err(|e| anyhow!("{}", e))?;
+        if !self.history {
+            self.crane.execute(m, &mut self.stacks)?;
+            self.step += 1;
+            return Ok(());
+        }
+        let before = [m.from, m.to]
+            .into_iter()
+            .filter_map(|label| stack_index(&self.stacks, label))
+            .map(|idx| (idx, self.stacks[idx].clone()))
+            .collect();
+        self.crane.execute(m, &mut self.stacks)?;
+        self.step += 1;
+        self.done.push(Applied { m: *m, before });
+        self.undone.clear();
+        Ok(())
+    }
+
+    /// Undo the last applied move and return it, if there is one in the history.
+    pub fn undo(&mut self) -> Option<Move> {
+        let applied = self.done.pop()?;
+        for (idx, stack) in applied.before {
+            self.stacks[idx] = stack;
+        }
+        self.step -= 1;
+        self.undone.push(applied.m);
+        Some(applied.m)
+    }
+
+    /// Apply the last undone move again and return it, if there is one.
+    pub fn redo(&mut self) -> Result<Option<Move>> {
+        let Some(m) = self.undone.pop() else {
+            return Ok(None);
+        };
+        let undone = std::mem::take(&mut self.undone);
+        self.apply(&m)?;
+        self.undone = undone;
+        Ok(Some(m))
+    }
+}
+
+/// Check `moves` against a copy of `stacks` and return the first one that is invalid,
+/// with the stacks as they were just before it.
+pub fn dry_run(
+    crane: &dyn Crane,
+    stacks: &[Stack],
+    moves: &[Move],
+    first_line: usize,
+) -> Result<Option<(InvalidMove, Vec<Stack>)>> {
+    let mut executor = Executor::new(crane, stacks.to_vec(), first_line);
+    for m in moves {
+        if let Err(invalid) = executor.validate(m) {
+            return Ok(Some((invalid, executor.stacks)));
+        }
+        executor.apply(m)?;
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crane::{Crane, CrateMover9000, TwoArm};
+    use crate::executor::{dry_run, Executor};
+    use crate::{first_move_line, read_stacks_and_moves, Move};
+
+    #[test]
+    fn test_undo_redo() {
+        let (stacks, moves) = read_stacks_and_moves("src/test_input.txt").unwrap();
+        for crane in [&CrateMover9000 as &dyn Crane, &TwoArm] {
+            let mut executor = Executor::new(crane, stacks.clone(), 6).with_history();
+            let mut states = vec![executor.stacks().to_vec()];
+            for m in moves.iter() {
+                executor.apply(m).unwrap();
+                states.push(executor.stacks().to_vec());
+            }
+            for step in (0..moves.len()).rev() {
+                assert_eq!(Some(moves[step]), executor.undo());
+                assert_eq!(states[step], executor.stacks());
+            }
+            assert_eq!(None, executor.undo());
+            executor.redo().unwrap();
+            executor.redo().unwrap();
+            assert_eq!(states[2], executor.stacks());
+            executor.apply(&moves[2]).unwrap();
+            assert_eq!(None, executor.redo().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let input = std::fs::read_to_string("src/test_input.txt").unwrap();
+        let (stacks, mut moves) = read_stacks_and_moves("src/test_input.txt").unwrap();
+        let first_line = first_move_line(&input);
+        assert_eq!(6, first_line);
+        assert!(dry_run(&CrateMover9000, &stacks, &moves, first_line)
+            .unwrap()
+            .is_none());
+
+        moves[2] = Move {
+            num: 3,
+            from: 2,
+            to: 1,
+        };
+        let (invalid, before) = dry_run(&CrateMover9000, &stacks, &moves, first_line)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            "line 8: move 3 from 2 to 1 takes 3 crates from stack 2 holding 2 (heights 1:0 2:2 3:4)",
+            invalid.to_string()
+        );
+        assert_eq!(
+            vec![0, 2, 4],
+            before.iter().map(|s| s.len()).collect::<Vec<_>>()
+        );
+
+        let mut executor = Executor::new(&CrateMover9000, stacks, first_line);
+        for m in [(1, 4, 1), (1, 1, 1)] {
+            let m = Move {
+                num: m.0,
+                from: m.1,
+                to: m.2,
+            };
+            assert!(executor.apply(&m).is_err());
+        }
+        assert_eq!(0, executor.step());
+        executor.apply(&moves[0]).unwrap();
+        assert_eq!((1, None), (executor.step(), executor.undo()));
+    }
+}
@@ -0,0 +1,381 @@
+//! Day 5: Supply Stacks.
+//!
+//! The input is a drawing of the starting crate stacks, a blank line, and then one
+//! `move N from A to B` instruction per line. Stacks are found by the labels under the
+//! drawing, which are numbers of any width, and each crate is a `[..]` cell of one or
+//! more characters above its stack's label. How the crates of a move are carried depends
+//! on the [`crane::Crane`] model.
+
+pub mod crane;
+pub mod executor;
+pub mod replay;
+pub mod search;
+
+use std::fmt::Display;
+
+use anyhow::{anyhow, Result};
+
+/// A `[..]` cell or a stack label, with the columns it spans.
+#[derive(Debug, Clone)]
+struct Token {
+    first: usize,
+    last: usize,
+    text: String,
+}
+
+/// The `[..]` cells of one drawing line, or an error naming anything else on it.
+fn cells(line: &str, line_no: usize) -> Result<Vec<Token>> {
+    let chars = line.chars().collect::<Vec<char>>();
+    let mut cells = Vec::new();
+    let mut idx = 0;
+    while idx < chars.len() {
+        match chars[idx] {
+            c if c.is_whitespace() => idx += 1,
+            '[' => {
+                let len = chars[idx + 1..]
+                    .iter()
+                    .position(|&c| c == ']')
+                    .ok_or_else(|| anyhow!("Unclosed crate on drawing line {}", line_no))?;
+                let text = chars[idx + 1..idx + 1 + len].iter().collect::<String>();
+                if text.is_empty() || text.contains(|c: char| c.is_whitespace() || c == '[') {
+                    return Err(anyhow!(
+                        "Invalid crate [{}] on drawing line {}",
+                        text,
+                        line_no
+                    ));
+                }
+                cells.push(Token {
+                    first: idx,
+                    last: idx + len + 1,
+                    text,
+                });
+                idx += len + 2;
+            }
+            c => {
+                return Err(anyhow!(
+                    "Unexpected {:?} outside a crate on drawing line {}",
+                    c,
+                    line_no
+                ))
+            }
+        }
+    }
+    Ok(cells)
+}
+
+/// The whitespace-separated tokens of the label row.
+fn labels(line: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut open = false;
+    for (idx, c) in line.chars().enumerate() {
+        if c.is_whitespace() {
+            open = false;
+        } else if open {
+            let token = tokens.last_mut().expect("open token");
+            token.last = idx;
+            token.text.push(c);
+        } else {
+            tokens.push(Token {
+                first: idx,
+                last: idx,
+                text: c.to_string(),
+            });
+            open = true;
+        }
+    }
+    tokens
+}
+
+/// Parse a stack drawing: rows of `[..]` crates above a row of numeric stack labels.
+/// Lines may be ragged, and each crate goes to the label its cell overlaps, preferring
+/// the one centred nearest to it.
+pub fn parse_drawing(drawing: &str) -> Result<Vec<Stack>> {
+    let lines = drawing
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .collect::<Vec<&str>>();
+    let (label_line, crate_lines) = lines
+        .split_last()
+        .ok_or_else(|| anyhow!("No stack labels found below the crate drawing"))?;
+    let label_tokens = labels(label_line);
+    let mut stacks = Vec::with_capacity(label_tokens.len());
+    for token in label_tokens.iter() {
+        let label = token
+            .text
+            .parse::<usize>()
+            .map_err(|_| anyhow!("Stack label is not a number: {}", token.text))?;
+        if stacks.iter().any(|s: &Stack| s.label == label) {
+            return Err(anyhow!("Stack label {} appears twice", label));
+        }
+        stacks.push(Stack::new(label));
+    }
+    if stacks.is_empty() {
+        return Err(anyhow!("No stack labels found below the crate drawing"));
+    }
+    for (idx, line) in crate_lines.iter().enumerate().rev() {
+        for cell in cells(line, idx + 1)? {
+            let centre = cell.first + cell.last;
+            let stack = label_tokens
+                .iter()
+                .enumerate()
+                .filter(|(_, t)| t.first <= cell.last && cell.first <= t.last)
+                .min_by_key(|(_, t)| centre.abs_diff(t.first + t.last))
+                .map(|(i, _)| i)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Crate [{}] on drawing line {} is not above a stack label",
+                        cell.text,
+                        idx + 1
+                    )
+                })?;
+            stacks[stack].push(cell.text);
+        }
+    }
+    Ok(stacks)
+}
+
+/// Draw `stacks` the way the puzzle input does, the inverse of [`parse_drawing`]. Every
+/// column is as wide as the widest crate cell or label and each entry is centred in it,
+/// so stacks of one-character crates labelled `1` to `9` come out exactly as in the input.
+pub fn render_drawing(stacks: &[Stack]) -> String {
+    let width = stacks
+        .iter()
+        .flat_map(|s| s.contents.iter().map(|c| c.chars().count() + 2))
+        .chain(stacks.iter().map(|s| s.label.to_string().len() + 2))
+        .max()
+        .unwrap_or(0);
+    let height = stacks.iter().map(Stack::len).max().unwrap_or(0);
+    let row = |cell: &dyn Fn(&Stack) -> String| {
+        let cells = stacks
+            .iter()
+            .map(|s| format!("{:^width$}", cell(s)))
+            .collect::<Vec<String>>();
+        cells.join(" ") + "\n"
+    };
+    let mut drawing = String::new();
+    for level in (0..height).rev() {
+        drawing.push_str(&row(&|s| match s.contents.get(level) {
+            Some(c) => format!("[{c}]"),
+            None => String::new(),
+        }));
+    }
+    drawing.push_str(&row(&|s| s.label.to_string()));
+    drawing
+}
+
+/// Read `input` into its starting stacks and list of moves.
+pub fn read_stacks_and_moves(input: &str) -> Result<(Vec<Stack>, Vec<Move>)> {
+    parse_stacks_and_moves(&std::fs::read_to_string(input)?)
+}
+
+/// The line of the first move in `input`: the one after the blank line that ends the
+/// drawing.
+pub fn first_move_line(input: &str) -> usize {
+    input.split("\n\n").next().map_or(0, |d| d.lines().count()) + 2
+}
+
+/// Parse the stack drawing and the moves that follow it.
+pub fn parse_stacks_and_moves(input: &str) -> Result<(Vec<Stack>, Vec<Move>)> {
+    let mut s = input.split("\n\n");
+    let stack_data = s
+        .next()
+        .ok_or_else(|| anyhow!("Failed to parse initial stack data"))?;
+    let parsed_stacks = parse_drawing(stack_data)?;
+
+    let move_data = s
+        .next()
+        .ok_or_else(|| anyhow!("Failed to parse initial moves data"))?;
+    let mut parsed_moves = Vec::new();
+    for line in move_data.lines() {
+        let mut tokens = line.split(' ');
+        let move_kw = tokens
+            .next()
+            .ok_or_else(|| anyhow!("Move line over but expected `move` keyword"))?;
+        if move_kw != "move" {
+            return Err(anyhow!("Expected `move` keyword, got {move_kw}"));
+        }
+        let num = tokens
+            .next()
+            .ok_or_else(|| anyhow!("Move line over but expected number of crates to move"))?
+            .parse::<usize>()?;
+        let from_kw = tokens
+            .next()
+            .ok_or_else(|| anyhow!("Move line over but expected `from` keyword"))?;
+        if from_kw != "from" {
+            return Err(anyhow!("Expected `from` keyword, got {from_kw}"));
+        }
+        let from_stack = tokens
+            .next()
+            .ok_or_else(|| anyhow!("Move line over but expected crate to move from"))?
+            .parse::<usize>()?;
+        let to_kw = tokens
+            .next()
+            .ok_or_else(|| anyhow!("Move line over but expected `to` keyword"))?;
+        if to_kw != "to" {
+            return Err(anyhow!("Expected `to` keyword, got {to_kw}"));
+        }
+        let to_stack = tokens
+            .next()
+            .ok_or_else(|| anyhow!("Move line over but expected crate to move to"))?
+            .parse::<usize>()?;
+        parsed_moves.push(Move {
+            num,
+            from: from_stack,
+            to: to_stack,
+        });
+    }
+    Ok((parsed_stacks, parsed_moves))
+}
+
+/// A labelled stack of crates, bottom first. A crate is its label of one or more
+/// characters.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Stack {
+    label: usize,
+    contents: Vec<String>,
+}
+
+impl Stack {
+    /// An empty stack labelled `label`.
+    pub fn new(label: usize) -> Self {
+        Self {
+            label,
+            contents: Vec::new(),
+        }
+    }
+    /// The stack's label.
+    pub fn label(&self) -> usize {
+        self.label
+    }
+    /// Put crate `c` on top of the stack.
+    pub fn push(&mut self, c: String) {
+        self.contents.push(c);
+    }
+    /// Take the top crate off the stack.
+    pub fn pop(&mut self) -> Option<String> {
+        self.contents.pop()
+    }
+    /// The crate on top of the stack, if any.
+    pub fn top_element(&self) -> Option<&str> {
+        self.contents.last().map(String::as_str)
+    }
+    /// The number of crates in the stack.
+    pub fn len(&self) -> usize {
+        self.contents.len()
+    }
+    /// Whether the stack holds no crates.
+    pub fn is_empty(&self) -> bool {
+        self.contents.is_empty()
+    }
+    /// The crates, bottom first.
+    pub fn crates(&self) -> &[String] {
+        &self.contents
+    }
+    /// Take the top `n` crates off the stack, bottom first, or `None` if there are fewer.
+    pub fn take_top(&mut self, n: usize) -> Option<Vec<String>> {
+        let at = self.contents.len().checked_sub(n)?;
+        Some(self.contents.split_off(at))
+    }
+    /// Take the bottom `n` crates out of the stack, bottom first, or `None` if there are
+    /// fewer.
+    pub fn take_bottom(&mut self, n: usize) -> Option<Vec<String>> {
+        if n > self.contents.len() {
+            return None;
+        }
+        Some(self.contents.drain(..n).collect())
+    }
+    /// Put `crates` on top of the stack, the first one lowest.
+    pub fn put(&mut self, crates: impl IntoIterator<Item = String>) {
+        self.contents.extend(crates);
+    }
+}
+
+/// The position in `stacks` of the stack labelled `label`.
+pub fn stack_index(stacks: &[Stack], label: usize) -> Option<usize> {
+    stacks.iter().position(|s| s.label == label)
+}
+
+/// The crates on top of each stack, read left to right. Empty stacks are skipped.
+pub fn top_message(stacks: &[Stack]) -> String {
+    let mut msg = String::new();
+    for s in stacks.iter() {
+        if let Some(c) = s.top_element() {
+            msg.push_str(c);
+        }
+    }
+    msg
+}
+
+/// Move `num` crates from the stack labelled `from` to the one labelled `to`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub num: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "move {} from {} to {}", self.num, self.from, self.to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fuzz::check_parser;
+
+    use crate::crane::{run, CrateMover9000, CrateMover9001};
+    use crate::top_message;
+    use crate::{parse_drawing, parse_stacks_and_moves, read_stacks_and_moves, stack_index};
+
+    #[test]
+    fn test_part_one() {
+        let (mut stacks, moves) = read_stacks_and_moves("src/test_input.txt").unwrap();
+        assert_eq!("CMZ", run(&CrateMover9000, &mut stacks, &moves, 6).unwrap());
+    }
+
+    #[test]
+    fn test_part_two() {
+        let (mut stacks, moves) = read_stacks_and_moves("src/test_input.txt").unwrap();
+        assert_eq!("MCD", run(&CrateMover9001, &mut stacks, &moves, 6).unwrap());
+    }
+
+    #[test]
+    fn test_drawing() {
+        let stacks = parse_drawing(concat!(
+            "[Q]                                     [Z]\n",
+            "[A] [B]                             [Y] [X]\n",
+            " 1   2   3   4   5   6   7   8   9   10  11 \n",
+        ))
+        .unwrap();
+        assert_eq!(11, stacks.len());
+        assert_eq!(Some(9), stack_index(&stacks, 10));
+        assert_eq!(["X", "Z"], stacks[10].crates());
+        assert_eq!("QBYZ", top_message(&stacks));
+
+        let (mut stacks, moves) =
+            parse_stacks_and_moves("[AB] [CD]\n[EF]\n  1    2\n\nmove 2 from 1 to 2\n").unwrap();
+        assert_eq!(["EF", "AB"], stacks[0].crates());
+        run(&CrateMover9000, &mut stacks, &moves, 5).unwrap();
+        assert_eq!(["CD", "AB", "EF"], stacks[1].crates());
+        assert_eq!("EF", top_message(&stacks));
+
+        assert!(parse_drawing("[A] x\n 1").is_err());
+        assert!(parse_drawing("[A]\n 1 1").is_err());
+        assert!(parse_drawing("[A]\n a").is_err());
+        assert!(parse_drawing("     [A]\n 1").is_err());
+        assert!(parse_drawing("[A]").is_err());
+    }
+
+    #[test]
+    fn test_fuzz_parsers() {
+        let base = std::fs::read_to_string("src/test_input.txt").unwrap();
+        check_parser(
+            "parse_stacks_and_moves",
+            &base,
+            5,
+            2_000,
+            parse_stacks_and_moves,
+        );
+    }
+}
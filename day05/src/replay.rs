@@ -0,0 +1,146 @@
+//! Replaying the moves one at a time, as drawings.
+//!
+//! Both [`replay`] and [`state_at`] apply the moves through an [`Executor`], so a bad move
+//! is reported with its line and the stack heights and no partial result is returned.
+
+use std::fmt::Display;
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+
+use crate::crane::Crane;
+use crate::executor::Executor;
+use crate::{render_drawing, Move, Stack};
+
+/// The drawing after a number of moves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// How many moves have been carried out.
+    pub step: usize,
+    /// The move that led here, or `None` for the starting stacks.
+    pub last_move: Option<Move>,
+    pub drawing: String,
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.last_move {
+            Some(m) => writeln!(f, "{}: {}", self.step, m)?,
+            None => writeln!(f, "start")?,
+        }
+        write!(f, "{}", self.drawing)
+    }
+}
+
+/// The starting drawing followed by the drawing after each move, with the first move on
+/// line `first_line` of the input.
+pub fn replay(
+    crane: &dyn Crane,
+    stacks: &[Stack],
+    moves: &[Move],
+    first_line: usize,
+) -> Result<Vec<Frame>> {
+    let mut executor = Executor::new(crane, stacks.to_vec(), first_line);
+    let mut frames = vec![Frame {
+        step: 0,
+        last_move: None,
+        drawing: render_drawing(executor.stacks()),
+    }];
+    for m in moves {
+        executor.apply(m)?;
+        frames.push(Frame {
+            step: executor.step(),
+            last_move: Some(*m),
+            drawing: render_drawing(executor.stacks()),
+        });
+    }
+    Ok(frames)
+}
+
+/// The stacks after the first `n` moves, with the first move on line `first_line` of the
+/// input.
+pub fn state_at(
+    crane: &dyn Crane,
+    stacks: &[Stack],
+    moves: &[Move],
+    n: usize,
+    first_line: usize,
+) -> Result<Vec<Stack>> {
+    let moves = moves.get(..n).ok_or_else(|| {
+        anyhow!(
+            "There are only {} moves, can't jump to move {}",
+            moves.len(),
+            n
+        )
+    })?;
+    let mut executor = Executor::new(crane, stacks.to_vec(), first_line);
+    for m in moves {
+        executor.apply(m)?;
+    }
+    Ok(executor.into_stacks())
+}
+
+/// Show `frames` one after another on a terminal, clearing it between them.
+pub fn animate(frames: &[Frame], delay: Duration, out: &mut impl Write) -> Result<()> {
+    for frame in frames {
+        write!(out, "\x1b[2J\x1b[H{}", frame)?;
+        out.flush()?;
+        std::thread::sleep(delay);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crane::{run, CrateMover9000};
+    use crate::replay::{replay, state_at};
+    use crate::{parse_drawing, read_stacks_and_moves, render_drawing, Move};
+
+    #[test]
+    fn test_render_round_trip() {
+        let input = std::fs::read_to_string("src/test_input.txt").unwrap();
+        let drawing = input.split("\n\n").next().unwrap().to_string() + "\n";
+        let stacks = parse_drawing(&drawing).unwrap();
+        assert_eq!(drawing, render_drawing(&stacks));
+
+        let wide = "[AB]          \n[CD] [E]      \n 1    2    10 \n";
+        let stacks = parse_drawing(wide).unwrap();
+        assert_eq!(wide, render_drawing(&stacks));
+        assert_eq!(stacks, parse_drawing(&render_drawing(&stacks)).unwrap());
+    }
+
+    #[test]
+    fn test_replay() {
+        let (stacks, moves) = read_stacks_and_moves("src/test_input.txt").unwrap();
+        let frames = replay(&CrateMover9000, &stacks, &moves, 6).unwrap();
+        assert_eq!(moves.len() + 1, frames.len());
+        assert_eq!(render_drawing(&stacks), frames[0].drawing);
+        assert!(frames[1]
+            .to_string()
+            .starts_with("1: move 1 from 2 to 1\n[D]        \n[N] [C]    \n"));
+
+        let at = state_at(&CrateMover9000, &stacks, &moves, 2, 6).unwrap();
+        assert_eq!(frames[2].drawing, render_drawing(&at));
+        let mut end = stacks.clone();
+        run(&CrateMover9000, &mut end, &moves, 6).unwrap();
+        assert_eq!(
+            end,
+            state_at(&CrateMover9000, &stacks, &moves, moves.len(), 6).unwrap()
+        );
+        assert!(state_at(&CrateMover9000, &stacks, &moves, moves.len() + 1, 6).is_err());
+
+        let mut bad = moves.clone();
+        bad[2] = Move {
+            num: 3,
+            from: 2,
+            to: 1,
+        };
+        let expected =
+            "line 8: move 3 from 2 to 1 takes 3 crates from stack 2 holding 2 (heights 1:0 2:2 3:4)";
+        let e = replay(&CrateMover9000, &stacks, &bad, 6).unwrap_err();
+        assert_eq!(expected, e.to_string());
+        let e = state_at(&CrateMover9000, &stacks, &bad, 3, 6).unwrap_err();
+        assert_eq!(expected, e.to_string());
+    }
+}
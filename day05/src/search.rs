@@ -0,0 +1,461 @@
+//! The shortest sequence of moves that reaches a target, under a given crane model.
+//!
+//! A target arrangement is searched with IDA*: depth-first searches bounded by the moves
+//! so far plus a lower bound on the moves left, with the bound raised to the smallest
+//! value that was cut off until a solution turns up. A move changes only the two stacks it
+//! touches, so half the number of stacks that differ from the target, rounded up, never
+//! overestimates. A table of the fewest moves each state was reached in prunes repeated
+//! states within an iteration, and a search that cuts nothing off has seen every reachable
+//! state, which proves the target unreachable.
+//!
+//! A top-of-stack message gives no useful bound, so it is searched breadth first over
+//! hashed states instead, which also settles unreachable messages in one pass.
+//!
+//! Both searches keep states packed into bytes, one per stack height and one per crate,
+//! with every crate replaced by a small id. The breadth-first search stores each state
+//! once and finds it again through a hash index of state ids, and the number of states
+//! either search keeps is capped at [`NODE_LIMIT`], so a hopeless search stops with an
+//! error long before it runs out of memory.
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+
+use anyhow::{anyhow, Result};
+
+use crate::crane::Crane;
+use crate::{top_message, Move, Stack};
+
+/// The most states [`shortest_moves`] will expand or keep, over all iterations.
+pub const NODE_LIMIT: usize = 5_000_000;
+
+/// What the search should reach.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// Exactly these stacks.
+    Stacks(Vec<Stack>),
+    /// Stacks whose [`top_message`] is this.
+    Message(String),
+}
+
+/// Packs stacks holding the crates of a starting arrangement into bytes: the height of
+/// each stack, then the id of every crate from the bottom of the first stack up.
+struct Packing {
+    labels: Vec<usize>,
+    names: Vec<String>,
+    ids: HashMap<String, u8>,
+}
+
+impl Packing {
+    fn new(start: &[Stack]) -> Result<Self> {
+        let total = start.iter().map(Stack::len).sum::<usize>();
+        if total > u8::MAX as usize {
+            return Err(anyhow!(
+                "Can't search with {} crates, the most is {}",
+                total,
+                u8::MAX
+            ));
+        }
+        let mut names = Vec::new();
+        let mut ids = HashMap::new();
+        for c in start.iter().flat_map(Stack::crates) {
+            ids.entry(c.clone()).or_insert_with(|| {
+                names.push(c.clone());
+                (names.len() - 1) as u8
+            });
+        }
+        Ok(Self {
+            labels: start.iter().map(Stack::label).collect(),
+            names,
+            ids,
+        })
+    }
+
+    /// `stacks`, which hold the starting crates, packed.
+    fn pack(&self, stacks: &[Stack]) -> Box<[u8]> {
+        stacks
+            .iter()
+            .map(|s| s.len() as u8)
+            .chain(stacks.iter().flat_map(Stack::crates).map(|c| self.ids[c]))
+            .collect()
+    }
+
+    fn unpack(&self, packed: &[u8]) -> Vec<Stack> {
+        let (heights, mut crates) = packed.split_at(self.labels.len());
+        self.labels
+            .iter()
+            .zip(heights)
+            .map(|(&label, &height)| {
+                let (here, rest) = crates.split_at(height as usize);
+                crates = rest;
+                let mut stack = Stack::new(label);
+                stack.put(here.iter().map(|&id| self.names[id as usize].clone()));
+                stack
+            })
+            .collect()
+    }
+}
+
+/// Every move `crane` can make from `stacks`, with the packed stacks it leads to. Each
+/// move is carried out on `stacks` and undone by putting back copies of the two stacks it
+/// touches.
+fn successors(
+    crane: &dyn Crane,
+    packing: &Packing,
+    stacks: &mut [Stack],
+) -> Vec<(Move, Box<[u8]>)> {
+    let mut next = Vec::new();
+    for from in 0..stacks.len() {
+        for to in (0..stacks.len()).filter(|&to| to != from) {
+            for num in 1..=stacks[from].len() {
+                let m = Move {
+                    num,
+                    from: stacks[from].label(),
+                    to: stacks[to].label(),
+                };
+                let saved = (stacks[from].clone(), stacks[to].clone());
+                if crane.execute(&m, stacks).is_ok() {
+                    next.push((m, packing.pack(stacks)));
+                    (stacks[from], stacks[to]) = saved;
+                }
+            }
+        }
+    }
+    next
+}
+
+/// Marks an empty slot in [`States`].
+const EMPTY: u32 = u32::MAX;
+
+/// Packed states of one size, stored back to back and numbered in the order they were
+/// added, with an open-addressing hash index of their numbers.
+struct States {
+    size: usize,
+    count: usize,
+    bytes: Vec<u8>,
+    slots: Vec<u32>,
+    hasher: RandomState,
+}
+
+impl States {
+    fn new(size: usize) -> Self {
+        Self {
+            size,
+            count: 0,
+            bytes: Vec::new(),
+            slots: vec![EMPTY; 16],
+            hasher: RandomState::new(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.count
+    }
+
+    fn get(&self, id: usize) -> &[u8] {
+        &self.bytes[id * self.size..(id + 1) * self.size]
+    }
+
+    /// Add `state` and return its number, or `None` if it is already stored.
+    fn insert(&mut self, state: &[u8]) -> Option<usize> {
+        if 2 * (self.count + 1) > self.slots.len() {
+            self.slots = vec![EMPTY; 2 * self.slots.len()];
+            for id in 0..self.count {
+                let slot = self.find(self.get(id));
+                self.slots[slot] = id as u32;
+            }
+        }
+        let slot = self.find(state);
+        if self.slots[slot] != EMPTY {
+            return None;
+        }
+        self.slots[slot] = self.count as u32;
+        self.bytes.extend_from_slice(state);
+        self.count += 1;
+        Some(self.count - 1)
+    }
+
+    /// The slot holding `state`, or the empty slot where it belongs.
+    fn find(&self, state: &[u8]) -> usize {
+        let mask = self.slots.len() - 1;
+        let mut slot = self.hasher.hash_one(state) as usize & mask;
+        while self.slots[slot] != EMPTY && self.get(self.slots[slot] as usize) != state {
+            slot = (slot + 1) & mask;
+        }
+        slot
+    }
+}
+
+fn node_limit_error(limit: usize) -> anyhow::Error {
+    anyhow!("No sequence found within {} expanded states", limit)
+}
+
+/// Half the stacks that differ from `target`, rounded up.
+fn lower_bound(target: &[Stack], stacks: &[Stack]) -> usize {
+    target
+        .iter()
+        .zip(stacks)
+        .filter(|(t, s)| t != s)
+        .count()
+        .div_ceil(2)
+}
+
+struct Search<'a> {
+    crane: &'a dyn Crane,
+    packing: &'a Packing,
+    target: &'a [Stack],
+    limit: usize,
+    /// The fewest moves each packed state was reached in during this iteration.
+    seen: HashMap<Box<[u8]>, usize>,
+    path: Vec<Move>,
+    expanded: usize,
+}
+
+impl Search<'_> {
+    /// Search below `stacks`, reached in `path.len()` moves, with total cost at most
+    /// `bound`. Returns whether the target was reached, or else the smallest cost that was
+    /// cut off.
+    fn visit(&mut self, stacks: &mut [Stack], bound: usize) -> Result<Result<(), Option<usize>>> {
+        let depth = self.path.len();
+        if self.target == stacks {
+            return Ok(Ok(()));
+        }
+        let cost = depth + lower_bound(self.target, stacks);
+        if cost > bound {
+            return Ok(Err(Some(cost)));
+        }
+        self.expanded += 1;
+        if self.expanded > self.limit {
+            return Err(node_limit_error(self.limit));
+        }
+        let mut next_bound = None::<usize>;
+        for (m, next) in successors(self.crane, self.packing, stacks) {
+            if self.seen.get(&next).is_some_and(|&d| d <= depth + 1) {
+                continue;
+            }
+            let mut child = self.packing.unpack(&next);
+            self.seen.insert(next, depth + 1);
+            if self.seen.len() > self.limit {
+                return Err(node_limit_error(self.limit));
+            }
+            self.path.push(m);
+            match self.visit(&mut child, bound)? {
+                Ok(()) => return Ok(Ok(())),
+                Err(cut) => {
+                    next_bound = match (next_bound, cut) {
+                        (Some(a), Some(b)) => Some(a.min(b)),
+                        (a, b) => a.or(b),
+                    }
+                }
+            }
+            self.path.pop();
+        }
+        Ok(Err(next_bound))
+    }
+}
+
+/// The fewest moves that take `start` to `target` with `crane`.
+pub fn shortest_moves(crane: &dyn Crane, start: &[Stack], target: &Target) -> Result<Vec<Move>> {
+    match target {
+        Target::Stacks(target) => deepening(crane, start, target, NODE_LIMIT),
+        Target::Message(msg) => breadth_first(crane, start, msg, NODE_LIMIT),
+    }
+}
+
+fn deepening(
+    crane: &dyn Crane,
+    start: &[Stack],
+    target: &[Stack],
+    limit: usize,
+) -> Result<Vec<Move>> {
+    let crates = |stacks: &[Stack]| {
+        let labels = stacks.iter().map(Stack::label).collect::<Vec<usize>>();
+        let mut crates = stacks
+            .iter()
+            .flat_map(|s| s.crates().iter().cloned())
+            .collect::<Vec<String>>();
+        crates.sort_unstable();
+        (labels, crates)
+    };
+    if crates(start) != crates(target) {
+        return Err(anyhow!(
+            "The target needs different stacks or crates than the start"
+        ));
+    }
+    let packing = Packing::new(start)?;
+    let mut search = Search {
+        crane,
+        packing: &packing,
+        target,
+        limit,
+        seen: HashMap::new(),
+        path: Vec::new(),
+        expanded: 0,
+    };
+    let mut bound = lower_bound(target, start);
+    loop {
+        search.seen.clear();
+        search.seen.insert(packing.pack(start), 0);
+        match search.visit(&mut start.to_vec(), bound)? {
+            Ok(()) => return Ok(search.path),
+            Err(Some(next)) => bound = next,
+            Err(None) => return Err(anyhow!("The target cannot be reached")),
+        }
+    }
+}
+
+fn breadth_first(crane: &dyn Crane, start: &[Stack], msg: &str, limit: usize) -> Result<Vec<Move>> {
+    let packing = Packing::new(start)?;
+    let first = packing.pack(start);
+    let mut states = States::new(first.len());
+    states.insert(&first);
+    // States are expanded in the order they are numbered, and state i was first reached
+    // from state parent[i].
+    let mut parent = vec![0];
+    let mut i = 0;
+    while i < states.len() {
+        let mut stacks = packing.unpack(states.get(i));
+        if top_message(&stacks) == msg {
+            let mut path = vec![i];
+            while i > 0 {
+                i = parent[i] as usize;
+                path.push(i);
+            }
+            path.reverse();
+            return Ok(path
+                .windows(2)
+                .map(|w| {
+                    let mut stacks = packing.unpack(states.get(w[0]));
+                    successors(crane, &packing, &mut stacks)
+                        .into_iter()
+                        .find(|(_, next)| **next == *states.get(w[1]))
+                        .map(|(m, _)| m)
+                        .expect("a state is one move from its parent")
+                })
+                .collect());
+        }
+        for (_, next) in successors(crane, &packing, &mut stacks) {
+            if states.insert(&next).is_some() {
+                if states.len() > limit {
+                    return Err(node_limit_error(limit));
+                }
+                parent.push(i as u32);
+            }
+        }
+        i += 1;
+    }
+    Err(anyhow!("No reachable stacks show the message {}", msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashSet, VecDeque};
+
+    use common::rng::Rng;
+
+    use crate::crane::{run, Crane, CrateMover9000, CrateMover9001, TwoArm};
+    use crate::replay::state_at;
+    use crate::search::{breadth_first, deepening, shortest_moves, Target};
+    use crate::{read_stacks_and_moves, top_message, Move, Stack};
+
+    /// The fewest moves to `target`, by breadth-first search.
+    fn bfs(crane: &dyn Crane, start: &[Stack], target: &Target) -> Option<usize> {
+        let mut seen = HashSet::from([start.to_vec()]);
+        let mut queue = VecDeque::from([(start.to_vec(), 0)]);
+        while let Some((stacks, depth)) = queue.pop_front() {
+            let reached = match target {
+                Target::Stacks(t) => *t == stacks,
+                Target::Message(msg) => top_message(&stacks) == *msg,
+            };
+            if reached {
+                return Some(depth);
+            }
+            for from in stacks.iter() {
+                for to in stacks.iter().filter(|s| s.label() != from.label()) {
+                    for num in 1..=from.len() {
+                        let m = Move {
+                            num,
+                            from: from.label(),
+                            to: to.label(),
+                        };
+                        let mut next = stacks.clone();
+                        crane.execute(&m, &mut next).unwrap();
+                        if seen.insert(next.clone()) {
+                            queue.push_back((next, depth + 1));
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_shortest_moves() {
+        let (start, moves) = read_stacks_and_moves("src/test_input.txt").unwrap();
+        let target = state_at(&CrateMover9000, &start, &moves, 2, 6).unwrap();
+        let solution =
+            shortest_moves(&CrateMover9000, &start, &Target::Stacks(target.clone())).unwrap();
+        assert_eq!(2, solution.len());
+        let mut stacks = start.clone();
+        run(&CrateMover9000, &mut stacks, &solution, 1).unwrap();
+        assert_eq!(target, stacks);
+
+        let solution =
+            shortest_moves(&CrateMover9001, &start, &Target::Message("MCD".into())).unwrap();
+        let mut stacks = start.clone();
+        assert_eq!(
+            "MCD",
+            run(&CrateMover9001, &mut stacks, &solution, 1).unwrap()
+        );
+        assert!(solution.len() <= moves.len());
+
+        let mut missing = target.clone();
+        missing[2].pop();
+        assert!(shortest_moves(&CrateMover9000, &start, &Target::Stacks(missing)).is_err());
+        assert!(shortest_moves(&CrateMover9000, &start, &Target::Message("QQQ".into())).is_err());
+    }
+
+    #[test]
+    fn test_node_limit() {
+        let (start, moves) = read_stacks_and_moves("src/test_input.txt").unwrap();
+        let target = state_at(&CrateMover9000, &start, &moves, 4, 6).unwrap();
+        for e in [
+            deepening(&CrateMover9000, &start, &target, 10).unwrap_err(),
+            breadth_first(&CrateMover9000, &start, "QQQ", 10).unwrap_err(),
+        ] {
+            assert_eq!("No sequence found within 10 expanded states", e.to_string());
+        }
+        assert!(deepening(&CrateMover9000, &start, &target, 1_000).is_ok());
+    }
+
+    #[test]
+    fn test_matches_bfs() {
+        let (start, _) = read_stacks_and_moves("src/test_input.txt").unwrap();
+        let mut rng = Rng::new(50);
+        let cranes: [&dyn Crane; 3] = [&CrateMover9000, &CrateMover9001, &TwoArm];
+        for round in 0..15 {
+            let crane = cranes[round % cranes.len()];
+            let mut stacks = start.clone();
+            for _ in 0..4 {
+                let from = rng.below(3);
+                let to = (from + 1 + rng.below(2)) % 3;
+                if stacks[from].is_empty() {
+                    continue;
+                }
+                let m = Move {
+                    num: 1 + rng.below(stacks[from].len()),
+                    from: from + 1,
+                    to: to + 1,
+                };
+                crane.execute(&m, &mut stacks).unwrap();
+            }
+            for target in [
+                Target::Stacks(stacks.clone()),
+                Target::Message(top_message(&stacks)),
+            ] {
+                let solution = shortest_moves(crane, &start, &target).unwrap();
+                assert_eq!(bfs(crane, &start, &target), Some(solution.len()));
+            }
+        }
+    }
+}
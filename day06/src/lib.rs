@@ -0,0 +1,96 @@
+//! Day 6: Tuning Trouble.
+//!
+//! The input is a single line of characters from the communication device's data stream.
+
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+
+/// Read `input` as raw bytes.
+pub fn read_data_stream(input: &str) -> Result<Vec<u8>> {
+    parse_data_stream(&std::fs::read_to_string(input)?)
+}
+
+/// The bytes of the first line of `data`, which must be lowercase ASCII letters.
+pub fn parse_data_stream(data: &str) -> Result<Vec<u8>> {
+    let line = data.lines().next().unwrap_or_default();
+    if let Some(c) = line.chars().find(|c| !c.is_ascii_lowercase()) {
+        return Err(anyhow!("Unexpected character {:?} in the data stream", c));
+    }
+    Ok(line.as_bytes().to_vec())
+}
+
+/// Whether every byte in `bytes` is distinct.
+pub fn all_unique(bytes: &[u8]) -> bool {
+    if bytes.len() > 255 {
+        false
+    } else {
+        let set: HashSet<u8> = HashSet::from_iter(bytes.iter().copied());
+        set.len() == bytes.len()
+    }
+}
+
+/// The number of characters read up to the end of the first run of `width` distinct bytes,
+/// or `None` if the stream has no such run or `width` is 0.
+pub fn marker_end(data_stream: &[u8], width: usize) -> Option<usize> {
+    if width == 0 {
+        return None;
+    }
+    data_stream
+        .windows(width)
+        .position(all_unique)
+        .map(|start| start + width)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fuzz::check_parser;
+
+    use crate::{marker_end, parse_data_stream, read_data_stream};
+
+    const EXAMPLES: [(&str, usize, usize); 5] = [
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+        ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+    ];
+
+    #[test]
+    fn test_part_one() {
+        let data_stream = read_data_stream("src/test_input.txt").unwrap();
+        assert_eq!(Some(7), marker_end(&data_stream, 4));
+        for (stream, end, _) in EXAMPLES {
+            assert_eq!(Some(end), marker_end(stream.as_bytes(), 4));
+        }
+    }
+
+    #[test]
+    fn test_part_two() {
+        let data_stream = read_data_stream("src/test_input.txt").unwrap();
+        assert_eq!(Some(19), marker_end(&data_stream, 14));
+        for (stream, _, end) in EXAMPLES {
+            assert_eq!(Some(end), marker_end(stream.as_bytes(), 14));
+        }
+    }
+
+    #[test]
+    fn test_marker_end() {
+        assert_eq!(Some(1), marker_end(b"aab", 1));
+        assert_eq!(Some(3), marker_end(b"aab", 2));
+        assert_eq!(None, marker_end(b"aab", 3));
+        assert_eq!(None, marker_end(b"abababab", 3));
+        assert_eq!(None, marker_end(b"abc", 4));
+        assert_eq!(None, marker_end(b"", 4));
+        assert_eq!(None, marker_end(b"abc", 0));
+        assert_eq!(None, marker_end(b"", 0));
+    }
+
+    #[test]
+    fn test_fuzz_parsers() {
+        let base = std::fs::read_to_string("src/test_input.txt").unwrap();
+        check_parser("parse_data_stream", &base, 6, 2_000, |s| {
+            parse_data_stream(s).map(|d| (marker_end(&d, 4), marker_end(&d, 14)))
+        });
+    }
+}
@@ -0,0 +1,361 @@
+//! Day 7: No Space Left On Device.
+//!
+//! The input is a terminal session of `cd` and `ls` commands, which is parsed into
+//! [`OutputLine`]s and then replayed into a [`Directory`] tree.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+use anyhow::{anyhow, Result};
+use common::checked;
+
+const DAY: &str = "day07";
+
+/// A file listed by `ls`.
+#[derive(Debug, Clone)]
+pub struct File {
+    pub size: usize,
+    pub name: String,
+}
+
+/// A command entered at the `$` prompt.
+#[derive(Debug, Clone)]
+pub enum Command {
+    Ls,
+    Cd(String),
+}
+
+impl Command {
+    /// Parse a command line with its `$ ` prompt already removed.
+    pub fn try_from_line(line: &str) -> Result<Self> {
+        let mut s = line.split(' ');
+        let cmd = s
+            .next()
+            .ok_or_else(|| anyhow!("Line is over but expected command keyword"))?;
+        match cmd {
+            "ls" => Ok(Self::Ls),
+            "cd" => {
+                let target_dir = s
+                    .next()
+                    .ok_or_else(|| anyhow!("Line is over but expected `cd` target directory"))?;
+                Ok(Self::Cd(target_dir.to_owned()))
+            }
+            c => Err(anyhow!("Unknown command encountered: {}", c)),
+        }
+    }
+}
+
+/// An entry listed by `ls`.
+#[derive(Debug, Clone)]
+pub enum Component {
+    File(File),
+    Directory(String),
+}
+
+impl Component {
+    /// Parse a line of `ls` output.
+    pub fn try_from_line(line: &str) -> Result<Self> {
+        let mut s = line.split(' ');
+        let first = s
+            .next()
+            .ok_or_else(|| anyhow!("Line is over but expected data"))?;
+        match first {
+            "dir" => {
+                let dir_name = s
+                    .next()
+                    .ok_or_else(|| anyhow!("Line is over but expected directory name"))?;
+                Ok(Self::Directory(dir_name.to_owned()))
+            }
+            f if f.is_ascii() => {
+                let size = f
+                    .parse::<usize>()
+                    .map_err(|_| anyhow!("Expected integer file size, got: {}", f))?;
+                let file_name = s
+                    .next()
+                    .ok_or_else(|| anyhow!("Line is over but expected file name"))?;
+                Ok(Self::File(File {
+                    size,
+                    name: file_name.to_owned(),
+                }))
+            }
+            c => Err(anyhow!("Unexepcted first element to data line: {}", c)),
+        }
+    }
+}
+
+/// A line of terminal output. The parsed session always ends with [`OutputLine::Eof`].
+#[derive(Debug, Clone)]
+pub enum OutputLine {
+    Command(Command),
+    Component(Component),
+    Eof,
+}
+
+/// A directory in the file system tree.
+#[derive(Debug, Clone)]
+pub struct Directory {
+    name: String,
+    files: Option<HashMap<String, File>>,
+    children: Option<HashMap<String, Rc<RefCell<Self>>>>,
+    parent: Option<Weak<RefCell<Self>>>,
+}
+
+impl Directory {
+    /// An empty directory with no parent.
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            files: None,
+            children: None,
+            parent: None,
+        }
+    }
+    /// The directory's own name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Add `new_dir` as a sub-directory called `name`.
+    pub fn add_child_dir(&mut self, name: String, new_dir: Rc<RefCell<Self>>) {
+        if let Some(ch) = &mut self.children {
+            ch.insert(name, new_dir);
+        } else {
+            self.children = Some(HashMap::from([(name, new_dir)]))
+        }
+    }
+    /// Add `file` to the directory under `name`.
+    pub fn add_file(&mut self, name: String, file: File) {
+        if let Some(fs) = &mut self.files {
+            fs.insert(name, file);
+        } else {
+            self.files = Some(HashMap::from([(name, file)]))
+        }
+    }
+    /// Set the directory that `cd ..` leads back to.
+    pub fn set_parent(&mut self, parent: Weak<RefCell<Self>>) {
+        self.parent = Some(parent);
+    }
+    /// A tree listing of the directory and everything below it.
+    pub fn print(&self, indent_level: usize) -> String {
+        let mut dir_idn = String::new();
+        for _ in 0..indent_level {
+            dir_idn += " ";
+        }
+        let mut idn = dir_idn.clone();
+        for _ in 0..2 {
+            idn += " ";
+        }
+        let mut s = String::new();
+        s += &format!("\n{}- {} (dir)\n", dir_idn, self.name);
+        if let Some(f) = &self.files {
+            s += &f
+                .iter()
+                .map(|(k, v)| format!("{}- {} (file, size={})", idn, k, v.size))
+                .collect::<Vec<String>>()
+                .join("\n");
+        }
+        if let Some(c) = &self.children {
+            s += &c
+                .values()
+                .map(|v| v.borrow().print(indent_level + 2))
+                .collect::<Vec<String>>()
+                .join("\n");
+        }
+        s
+    }
+    /// The total size of the directory, pushing `(name, size)` for it and every
+    /// sub-directory onto `dir_vec`.
+    pub fn total_size(&self, dir_vec: &mut Vec<(String, usize)>) -> Result<usize> {
+        let mut dir_size = 0usize;
+        if let Some(f) = &self.files {
+            dir_size = checked::sum(DAY, "directory file sizes", f.values().map(|v| v.size))?;
+        }
+        if let Some(c) = &self.children {
+            for v in c.values() {
+                let child_size = v.borrow().total_size(dir_vec)?;
+                dir_size = checked::add(DAY, "directory total size", dir_size, child_size)?;
+            }
+        }
+        dir_vec.push((self.name.clone(), dir_size));
+        Ok(dir_size)
+    }
+}
+
+/// The summed size of every directory in `dir_sizes` of at most 100,000 bytes.
+pub fn small_directory_sum(dir_sizes: &[(String, usize)]) -> Result<usize> {
+    Ok(checked::sum(
+        DAY,
+        "small directory size sum",
+        dir_sizes
+            .iter()
+            .filter_map(|&(_, s)| (s <= 100_000).then_some(s)),
+    )?)
+}
+
+/// The size of the smallest directory whose deletion frees enough space for the update,
+/// given the `root_size` of the whole file system.
+pub fn smallest_directory_to_free(
+    root_size: usize,
+    dir_sizes: &[(String, usize)],
+) -> Result<usize> {
+    let total_space = 70_000_000;
+    let needed_space = 30_000_000;
+    let unused_space = checked::sub(DAY, "unused space", total_space, root_size)?;
+    let target = checked::sub(DAY, "space to free", needed_space, unused_space)?;
+    dir_sizes
+        .iter()
+        .filter_map(|&(_, s)| ((s as isize - target as isize) > 0).then_some(s))
+        .min()
+        .ok_or_else(|| anyhow!("No directory is large enough to free {} bytes", target))
+}
+
+/// Read `input` as a terminal session.
+pub fn parse_raw_output(input: &str) -> Result<Vec<OutputLine>> {
+    parse_output_lines(&std::fs::read_to_string(input)?)
+}
+
+/// Parse each line of a terminal session.
+pub fn parse_output_lines(data: &str) -> Result<Vec<OutputLine>> {
+    let mut output_lines = Vec::new();
+    for line in data.lines() {
+        if let Some(cmd) = line.strip_prefix('$') {
+            let cmd = cmd
+                .strip_prefix(' ')
+                .ok_or_else(|| anyhow!("Expected space after `$` prompt, got: {}", line))?;
+            output_lines.push(OutputLine::Command(Command::try_from_line(cmd)?));
+        } else {
+            output_lines.push(OutputLine::Component(Component::try_from_line(line)?));
+        }
+    }
+    output_lines.push(OutputLine::Eof);
+    Ok(output_lines)
+}
+
+/// Replay a parsed terminal session and return the root directory.
+pub fn parse_intermediate_representation(ir: &[OutputLine]) -> Result<Rc<RefCell<Directory>>> {
+    let ir_len = ir.len();
+    let root = Rc::new(RefCell::new(Directory::new("/".to_owned())));
+    let mut ir_cursor = 0;
+    let mut current = Rc::clone(&root);
+    while ir_cursor < ir_len {
+        match &ir[ir_cursor] {
+            OutputLine::Command(cmd) => match cmd {
+                Command::Cd(s) if matches!(s.as_str(), "/") => {
+                    current = Rc::clone(&root);
+                    ir_cursor += 1;
+                }
+                Command::Cd(s) if matches!(s.as_str(), "..") => {
+                    let target;
+                    if let Some(p) = &current.borrow().parent {
+                        target = Rc::clone(
+                            &p.upgrade()
+                                .ok_or_else(|| anyhow!("Could not `upgrade` weak ref"))?,
+                        );
+                    } else {
+                        return Err(anyhow!(
+                            "Tried to access non-existent parent for directory {}",
+                            current.borrow().name
+                        ));
+                    }
+                    current = target;
+                    ir_cursor += 1;
+                }
+                Command::Cd(s) => {
+                    let target;
+                    if let Some(dirs) = &current.borrow().children {
+                        target = Rc::clone(dirs.get(s).ok_or_else(|| {
+                            anyhow!("Tried to access non-existent directory: {}", &s)
+                        })?);
+                    } else {
+                        return Err(anyhow!(
+                            "No sub-directories associated with current directory: {}",
+                            current.borrow().name
+                        ));
+                    }
+                    let tmp = Weak::clone(&Rc::downgrade(&current));
+                    current = target;
+                    current.borrow_mut().set_parent(tmp);
+                    ir_cursor += 1;
+                }
+                Command::Ls => loop {
+                    if ir_cursor + 1 >= ir_len {
+                        break;
+                    }
+                    match &ir[ir_cursor + 1] {
+                        OutputLine::Component(Component::File(f)) => {
+                            current.borrow_mut().add_file(f.name.clone(), f.clone());
+                            ir_cursor += 1;
+                        }
+                        OutputLine::Component(Component::Directory(d)) => {
+                            let new_dir = Directory::new(d.clone());
+                            current
+                                .borrow_mut()
+                                .add_child_dir(d.clone(), Rc::new(RefCell::new(new_dir)));
+                            ir_cursor += 1;
+                        }
+                        _ => {
+                            ir_cursor += 1;
+                            break;
+                        }
+                    }
+                },
+            },
+            OutputLine::Eof => {
+                break;
+            }
+            OutputLine::Component(_) => {
+                return Err(anyhow!("Unexpected `Component` in outer loop"))
+            }
+        }
+    }
+    Ok(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fuzz::check_parser;
+
+    use crate::{parse_intermediate_representation, parse_output_lines, parse_raw_output};
+
+    #[test]
+    fn test_part_one() {
+        let file_sys =
+            parse_intermediate_representation(&parse_raw_output("src/test_input.txt").unwrap())
+                .unwrap();
+        let mut dir_size_vec = Vec::new();
+        let _root_size = file_sys.borrow().total_size(&mut dir_size_vec).unwrap();
+        let part_one_sum = dir_size_vec
+            .iter()
+            .filter_map(|&(_, s)| (s <= 100_000).then_some(s))
+            .sum::<usize>();
+        assert_eq!(95_437, part_one_sum);
+    }
+
+    #[test]
+    fn test_part_two() {
+        let file_sys =
+            parse_intermediate_representation(&parse_raw_output("src/test_input.txt").unwrap())
+                .unwrap();
+        let mut dir_size_vec = Vec::new();
+        let root_size = file_sys.borrow().total_size(&mut dir_size_vec).unwrap();
+        let total_space = 70_000_000;
+        let needed_space = 30_000_000;
+        let unused_space = total_space - root_size;
+        let target = needed_space - unused_space;
+
+        let part_two_min = dir_size_vec
+            .iter()
+            .filter_map(|&(_, s)| ((s as isize - target as isize) > 0).then_some(s))
+            .min()
+            .unwrap();
+        assert_eq!(24_933_642, part_two_min);
+    }
+
+    #[test]
+    fn test_fuzz_parsers() {
+        let base = std::fs::read_to_string("src/test_input.txt").unwrap();
+        check_parser("parse_output_lines", &base, 7, 2_000, |s| {
+            parse_intermediate_representation(&parse_output_lines(s)?)
+        });
+    }
+}
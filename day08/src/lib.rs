@@ -0,0 +1,245 @@
+//! Day 8: Treetop Tree House.
+//!
+//! The input is a rectangular grid of tree heights, one digit per tree.
+
+use anyhow::{anyhow, Result};
+use common::checked;
+
+const DAY: &str = "day08";
+
+/// A rectangular grid of tree heights.
+#[derive(Debug, Clone)]
+pub struct Grid {
+    shape: (usize, usize),
+    trees: Vec<Vec<u8>>,
+}
+
+impl Grid {
+    /// A grid from rows of heights, or an error if it is empty or ragged.
+    pub fn from_vecs(vecs: Vec<Vec<u8>>) -> Result<Self> {
+        let num_rows = vecs.len();
+        let num_cols = vecs
+            .first()
+            .ok_or_else(|| anyhow!("Grid has no rows"))?
+            .len();
+        if num_cols == 0 {
+            return Err(anyhow!("Grid has no columns"));
+        }
+        if let Some((idx, r)) = vecs.iter().enumerate().find(|(_, r)| r.len() != num_cols) {
+            return Err(anyhow!(
+                "Row {} has {} trees, expected {}",
+                idx,
+                r.len(),
+                num_cols
+            ));
+        }
+        Ok(Self {
+            shape: (num_rows, num_cols),
+            trees: vecs,
+        })
+    }
+    /// The number of rows and columns.
+    pub fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+    /// Whether the tree at `row`, `col` can be seen from outside the grid.
+    pub fn is_tree_visible(&self, row: usize, col: usize) -> bool {
+        let height = self.trees[row][col];
+        let taller_on_left = self.trees[row]
+            .iter()
+            .take(col)
+            .map(|&t| t >= height)
+            .filter(|b| *b)
+            .count()
+            > 0;
+        if !taller_on_left {
+            return true;
+        }
+        let taller_on_right = self.trees[row]
+            .iter()
+            .skip(col + 1)
+            .map(|&t| t >= height)
+            .filter(|b| *b)
+            .count()
+            > 0;
+        if !taller_on_right {
+            return true;
+        }
+        let taller_above = self
+            .trees
+            .iter()
+            .take(row)
+            .map(|r| r[col] >= height)
+            .filter(|b| *b)
+            .count()
+            > 0;
+        if !taller_above {
+            return true;
+        }
+        let taller_below = self
+            .trees
+            .iter()
+            .skip(row + 1)
+            .map(|r| r[col] >= height)
+            .filter(|b| *b)
+            .count()
+            > 0;
+        if !taller_below {
+            return true;
+        }
+        false
+    }
+    /// The product of the viewing distances from the tree at `row`, `col`
+    /// in each of the four directions.
+    pub fn scenic_score(&self, row: usize, col: usize) -> Result<usize> {
+        let height = self.trees[row][col];
+        let mut trees_on_left = 0;
+        let mut trees_on_right = 0;
+        let mut trees_above = 0;
+        let mut trees_below = 0;
+        for &t in self.trees[row].iter().take(col).rev() {
+            trees_on_left += 1;
+            if t >= height {
+                break;
+            }
+        }
+        for &t in self.trees[row].iter().skip(col + 1) {
+            trees_on_right += 1;
+            if t >= height {
+                break;
+            }
+        }
+        for t in self.trees.iter().take(row).rev().map(|r| r[col]) {
+            trees_above += 1;
+            if t >= height {
+                break;
+            }
+        }
+        for t in self.trees.iter().skip(row + 1).map(|r| r[col]) {
+            trees_below += 1;
+            if t >= height {
+                break;
+            }
+        }
+        Ok(checked::product(
+            DAY,
+            "scenic score",
+            [trees_on_left, trees_on_right, trees_above, trees_below],
+        )?)
+    }
+    /// The number of trees visible from outside the grid.
+    pub fn num_visible_trees(&self) -> usize {
+        let (nr, nc) = self.shape;
+        let mut num_visible_trees = 2 * nc + 2 * nr - 4;
+        for r in 1..(nr - 1) {
+            for c in 1..(nc - 1) {
+                if self.is_tree_visible(r, c) {
+                    num_visible_trees += 1;
+                }
+            }
+        }
+        num_visible_trees
+    }
+    /// The highest scenic score of any interior tree.
+    pub fn top_scenic_score(&self) -> Result<usize> {
+        let (nr, nc) = self.shape;
+        let mut top_scenic_score = 0;
+        for r in 1..(nr - 1) {
+            for c in 1..(nc - 1) {
+                let scenic_score = self.scenic_score(r, c)?;
+                if scenic_score > top_scenic_score {
+                    top_scenic_score = scenic_score;
+                }
+            }
+        }
+        Ok(top_scenic_score)
+    }
+    /// The grid drawn with visible trees as their height and hidden trees as `.`.
+    pub fn visibility_map(&self) -> String {
+        let (nr, nc) = self.shape;
+        let mut map = String::with_capacity(nr * (nc + 1));
+        for r in 0..nr {
+            for c in 0..nc {
+                if self.is_tree_visible(r, c) {
+                    map.push((b'0' + self.trees[r][c]) as char);
+                } else {
+                    map.push('.');
+                }
+            }
+            map.push('\n');
+        }
+        map
+    }
+}
+
+/// Read `input` as a tree grid.
+pub fn read_grid(input: &str) -> Result<Grid> {
+    parse_grid(&std::fs::read_to_string(input)?)
+}
+
+/// Parse one row of digit heights per line.
+pub fn parse_grid(data: &str) -> Result<Grid> {
+    let mut rows = Vec::new();
+    for row in data.lines() {
+        let mut r = Vec::new();
+        for c in row.chars() {
+            if !c.is_ascii_digit() {
+                return Err(anyhow!("Encountered non digit {}", c));
+            } else {
+                r.push(
+                    c.to_digit(10)
+                        .ok_or_else(|| anyhow!("Could not parse {} as digit", c))?
+                        as u8,
+                );
+            }
+        }
+        rows.push(r);
+    }
+    Grid::from_vecs(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fuzz::check_parser;
+
+    use crate::{parse_grid, read_grid};
+
+    #[test]
+    fn test_part_one() {
+        let grid = read_grid("src/test_input.txt").unwrap();
+        let (nr, nc) = grid.shape;
+        let mut num_visible_trees = 2 * nc + 2 * nr - 4;
+        for r in 1..(nr - 1) {
+            for c in 1..(nc - 1) {
+                if grid.is_tree_visible(r, c) {
+                    num_visible_trees += 1;
+                }
+            }
+        }
+        assert_eq!(21, num_visible_trees);
+    }
+
+    #[test]
+    fn test_part_two() {
+        let grid = read_grid("src/test_input.txt").unwrap();
+        let (nr, nc) = grid.shape;
+        let mut top_scenic_score = 0;
+        for r in 1..(nr - 1) {
+            for c in 1..(nc - 1) {
+                let scenic_score = grid.scenic_score(r, c).unwrap();
+                if scenic_score > top_scenic_score {
+                    top_scenic_score = scenic_score;
+                }
+            }
+        }
+        assert_eq!(8, top_scenic_score);
+    }
+
+    #[test]
+    fn test_fuzz_parsers() {
+        let base = std::fs::read_to_string("src/test_input.txt").unwrap();
+        check_parser("parse_grid", &base, 8, 2_000, |s| {
+            parse_grid(s).map(|g| g.visibility_map())
+        });
+    }
+}
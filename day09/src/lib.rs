@@ -0,0 +1,202 @@
+//! Day 9: Rope Bridge.
+//!
+//! Each line moves the head of the rope, e.g. `R 4`, and the knots behind it follow.
+
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+
+/// Move the head some number of steps in one direction.
+#[derive(Debug, Copy, Clone)]
+pub enum Move {
+    Right(u8),
+    Left(u8),
+    Up(u8),
+    Down(u8),
+}
+
+impl Move {
+    /// Parse a line such as `R 4`.
+    pub fn try_from_line(line: &str) -> Result<Self> {
+        let mut s = line.split(' ');
+        let direction = s
+            .next()
+            .ok_or_else(|| anyhow!("Line is over but expected direction keyword"))?;
+        let num_steps = s
+            .next()
+            .ok_or_else(|| anyhow!("Line is over but expected number of steps"))?
+            .parse::<u8>()
+            .map_err(|e| anyhow!("Could not parse {:?} as `u8`: {}", s, e))?;
+        match (direction, num_steps) {
+            ("R", n) => Ok(Self::Right(n)),
+            ("L", n) => Ok(Self::Left(n)),
+            ("U", n) => Ok(Self::Up(n)),
+            ("D", n) => Ok(Self::Down(n)),
+            (d, _) => Err(anyhow!("Unexpected direction keyword {}", d)),
+        }
+    }
+}
+
+/// A position on the grid, with `y` increasing upwards.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Default)]
+pub struct Location {
+    pub x: isize,
+    pub y: isize,
+}
+
+/// A rope of `N` knots, all starting at the origin, and the set of locations
+/// its tail has visited.
+#[derive(Debug, Clone)]
+pub struct SimState<const N: usize> {
+    locs: [Location; N],
+    tl_loc_set: HashSet<Location>,
+}
+
+impl<const N: usize> Default for SimState<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> SimState<N> {
+    /// A rope with every knot at the origin.
+    pub fn new() -> Self {
+        SimState {
+            locs: [Location::default(); N],
+            tl_loc_set: HashSet::new(),
+        }
+    }
+    /// The number of distinct locations the tail has visited, including the start.
+    pub fn tail_visits(&self) -> usize {
+        self.tl_loc_set.len()
+    }
+    /// Move the head step by step, letting the rest of the rope follow.
+    pub fn advance_move(&mut self, mv: Move) {
+        match mv {
+            Move::Right(n) => {
+                for _ in 0..n {
+                    self.advance_right();
+                }
+            }
+            Move::Left(n) => {
+                for _ in 0..n {
+                    self.advance_left();
+                }
+            }
+            Move::Up(n) => {
+                for _ in 0..n {
+                    self.advance_up();
+                }
+            }
+            Move::Down(n) => {
+                for _ in 0..n {
+                    self.advance_down();
+                }
+            }
+        }
+    }
+
+    fn advance_right(&mut self) {
+        self.locs[0].x += 1;
+        self.advance();
+    }
+
+    fn advance_left(&mut self) {
+        self.locs[0].x -= 1;
+        self.advance();
+    }
+
+    fn advance_up(&mut self) {
+        self.locs[0].y += 1;
+        self.advance();
+    }
+
+    fn advance_down(&mut self) {
+        self.locs[0].y -= 1;
+        self.advance();
+    }
+
+    fn advance(&mut self) {
+        for hd_idx in 0..N - 1 {
+            self.update_tail_loc(hd_idx);
+        }
+        self.tl_loc_set.insert(self.locs[N - 1]);
+    }
+
+    fn update_tail_loc(&mut self, hd_idx: usize) {
+        let (hd, tl) = (self.locs[hd_idx], self.locs[hd_idx + 1]);
+        let x_diff = hd.x - tl.x;
+        let y_diff = hd.y - tl.y;
+        let tl_loc = &mut self.locs[hd_idx + 1];
+        match (x_diff, y_diff) {
+            (0, 0) | (1, 0) | (0, 1) | (-1, 0) | (0, -1) => {}
+            (1, 1) | (1, -1) | (-1, 1) | (-1, -1) => {}
+            (n, 0) if n >= 1 => tl_loc.x += 1,
+            (n, 0) if n <= -1 => tl_loc.x -= 1,
+            (0, n) if n >= 1 => tl_loc.y += 1,
+            (0, n) if n <= -1 => tl_loc.y -= 1,
+            (nx, ny) if nx.is_positive() && ny.is_positive() => {
+                tl_loc.x += 1;
+                tl_loc.y += 1;
+            }
+            (nx, ny) if nx.is_positive() && ny.is_negative() => {
+                tl_loc.x += 1;
+                tl_loc.y -= 1;
+            }
+            (nx, ny) if nx.is_negative() && ny.is_positive() => {
+                tl_loc.x -= 1;
+                tl_loc.y += 1;
+            }
+            (nx, ny) if nx.is_negative() && ny.is_negative() => {
+                tl_loc.x -= 1;
+                tl_loc.y -= 1;
+            }
+            (_, _) => {}
+        }
+    }
+}
+
+/// Read `input` as a list of head moves.
+pub fn read_moves(input: &str) -> Result<Vec<Move>> {
+    parse_moves(&std::fs::read_to_string(input)?)
+}
+
+/// Parse one head move per line.
+pub fn parse_moves(data: &str) -> Result<Vec<Move>> {
+    let mut moves = Vec::new();
+    for line in data.lines() {
+        moves.push(Move::try_from_line(line)?);
+    }
+    Ok(moves)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fuzz::check_parser;
+
+    use crate::{parse_moves, read_moves, SimState};
+
+    #[test]
+    fn test_part_one() {
+        let mut sim = SimState::<2>::new();
+        for m in read_moves("src/test_input.txt").unwrap().into_iter() {
+            sim.advance_move(m);
+        }
+        assert_eq!(13, sim.tl_loc_set.len());
+    }
+
+    #[test]
+    fn test_part_two() {
+        let mut sim = SimState::<10>::new();
+        for m in read_moves("src/test_input2.txt").unwrap().into_iter() {
+            sim.advance_move(m);
+        }
+        assert_eq!(36, sim.tl_loc_set.len());
+    }
+
+    #[test]
+    fn test_fuzz_parsers() {
+        let base = std::fs::read_to_string("src/test_input2.txt").unwrap();
+        check_parser("parse_moves", &base, 9, 2_000, parse_moves);
+    }
+}
@@ -0,0 +1,179 @@
+//! Day 10: Cathode-Ray Tube.
+//!
+//! The input is a program of `noop` and `addx V` instructions for a single-register CPU.
+
+use anyhow::{anyhow, Result};
+use common::checked;
+
+const DAY: &str = "day10";
+
+/// A single CPU instruction.
+#[derive(Debug, Copy, Clone)]
+pub enum Instruction {
+    Noop,
+    Addx(isize),
+}
+
+impl Instruction {
+    /// The number of cycles the instruction takes to complete.
+    pub fn num_cycles(&self) -> usize {
+        match self {
+            Self::Noop => 1,
+            Self::Addx(_) => 2,
+        }
+    }
+
+    /// Parse a `noop` or `addx V` line.
+    pub fn try_from_line(line: &str) -> Result<Self> {
+        let mut s = line.split(' ');
+        match s
+            .next()
+            .ok_or_else(|| anyhow!("Line over but expected instruction"))?
+        {
+            "addx" => {
+                let add_arg = s
+                    .next()
+                    .ok_or_else(|| anyhow!("Line over but expected argument for `addx`"))?;
+                Ok(Self::Addx(add_arg.parse::<isize>()?))
+            }
+            "noop" => Ok(Self::Noop),
+            c => Err(anyhow!("Unsupported command: {}", c)),
+        }
+    }
+}
+
+/// The CPU, with its `X` register and a record of the register during each cycle.
+#[derive(Debug, Clone)]
+pub struct VM {
+    reg: isize,
+    current_cycle: usize,
+    history: Vec<(isize, usize)>,
+}
+
+impl Default for VM {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VM {
+    /// A CPU at cycle 1 with `X = 1`.
+    pub fn new() -> Self {
+        Self {
+            reg: 1,
+            current_cycle: 1,
+            history: vec![(1, 1)],
+        }
+    }
+    /// Run `instruction` to completion.
+    pub fn exe_instruction(&mut self, instruction: Instruction) -> Result<()> {
+        match instruction {
+            Instruction::Noop => {
+                for _ in 0..instruction.num_cycles() {
+                    self.cycle();
+                }
+            }
+            Instruction::Addx(arg) => {
+                for _ in 0..instruction.num_cycles() - 1 {
+                    self.cycle();
+                }
+                self.reg = checked::add(DAY, "`addx` register update", self.reg, arg)?;
+                self.cycle();
+            }
+        }
+        Ok(())
+    }
+    /// The `(X, cycle)` pair for every cycle so far. Index `i` is cycle `i + 1`.
+    pub fn history(&self) -> &[(isize, usize)] {
+        &self.history
+    }
+    /// The summed signal strength during cycles 20, 60, 100, 140, 180 and 220.
+    pub fn signal_strength_sum(&self) -> Result<isize> {
+        let mut ss_sum = 0;
+        for ss_idx in (19..).step_by(40).take(6) {
+            let &(r, c) = self
+                .history
+                .get(ss_idx)
+                .ok_or_else(|| anyhow!("Program ended before cycle {}", ss_idx + 1))?;
+            let strength = checked::mul(DAY, "signal strength", c as isize, r)?;
+            ss_sum = checked::add(DAY, "signal strength sum", ss_sum, strength)?;
+        }
+        Ok(ss_sum)
+    }
+    fn cycle(&mut self) {
+        self.current_cycle += 1;
+        self.history.push((self.reg, self.current_cycle));
+    }
+}
+
+/// Read `input` as a program.
+pub fn read_program(input: &str) -> Result<Vec<Instruction>> {
+    parse_program(&std::fs::read_to_string(input)?)
+}
+
+/// Parse one instruction per line.
+pub fn parse_program(data: &str) -> Result<Vec<Instruction>> {
+    let mut program = Vec::new();
+    for line in data.lines() {
+        program.push(Instruction::try_from_line(line)?);
+    }
+    Ok(program)
+}
+
+/// Draw the 40x6 screen from the register `history`, lighting each pixel the
+/// 3-wide sprite covers while it is drawn.
+pub fn draw_crt(history: &[(isize, usize)], crt: &mut [[char; 40]; 6]) {
+    let mut cycle_idx = 0;
+    for row in crt.iter_mut() {
+        for (col_idx, col) in row.iter_mut().enumerate() {
+            let sprite_loc = history[cycle_idx].0;
+            let c = if (col_idx as isize - sprite_loc).abs() <= 1 {
+                '#'
+            } else {
+                '.'
+            };
+            *col = c;
+            cycle_idx += 1;
+        }
+    }
+}
+
+/// The screen as text, one line per row.
+pub fn crt_to_string(crt: &[[char; 40]; 6]) -> String {
+    let mut s = String::new();
+    for row in crt.iter() {
+        s.extend(row.iter());
+        s.push('\n');
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fuzz::check_parser;
+
+    use crate::{parse_program, read_program, VM};
+
+    #[test]
+    fn test_part_one() {
+        let mut vm = VM::new();
+        for instruction in read_program("src/test_input.txt").unwrap() {
+            vm.exe_instruction(instruction).unwrap();
+        }
+        let mut ss_sum = 0;
+        let mut ss_idx = 19;
+        for _ in 0..6 {
+            let (r, c) = vm.history[ss_idx];
+            println!("{}-th cycle reg: {}, {}", ss_idx, c, r);
+            ss_sum += c as isize * r;
+            ss_idx += 40;
+        }
+        assert_eq!(13_140, ss_sum);
+    }
+
+    #[test]
+    fn test_fuzz_parsers() {
+        let base = std::fs::read_to_string("src/test_input.txt").unwrap();
+        check_parser("parse_program", &base, 10, 2_000, parse_program);
+    }
+}
@@ -0,0 +1,235 @@
+//! Day 11: Monkey in the Middle.
+//!
+//! The input describes each monkey's starting items, how it changes an item's worry
+//! level, and which monkey it throws to depending on a divisibility test.
+
+use std::collections::VecDeque;
+
+use anyhow::{anyhow, Result};
+use common::checked;
+
+const DAY: &str = "day11";
+
+/// How a monkey changes an item's worry level when inspecting it.
+#[derive(Debug, Copy, Clone)]
+pub enum Operation {
+    Mul(usize),
+    Add(usize),
+    Square,
+    Double,
+}
+
+/// A monkey, the items it holds and how many items it has inspected.
+#[derive(Debug, Clone)]
+pub struct Monkey {
+    items: VecDeque<usize>,
+    operation: Operation,
+    divisor: usize,
+    true_midx: usize,
+    false_midx: usize,
+    inspection_count: usize,
+}
+
+impl Monkey {
+    /// A monkey that has not inspected anything yet.
+    pub fn new(
+        items: VecDeque<usize>,
+        operation: Operation,
+        divisor: usize,
+        true_midx: usize,
+        false_midx: usize,
+    ) -> Self {
+        Self {
+            items,
+            operation,
+            divisor,
+            true_midx,
+            false_midx,
+            inspection_count: 0,
+        }
+    }
+    fn pop(&mut self) -> Option<usize> {
+        self.items.pop_front()
+    }
+    /// The number of items this monkey has inspected.
+    pub fn inspection_count(&self) -> usize {
+        self.inspection_count
+    }
+    fn push(&mut self, item: usize) {
+        self.items.push_back(item)
+    }
+    /// Parse one `Monkey N:` block.
+    pub fn try_from_str(s: &str) -> Result<Self> {
+        let mut items = VecDeque::new();
+        let mut lines = s.split('\n');
+        let monkey_label = lines
+            .next()
+            .ok_or_else(|| anyhow!("Raw monkey block ended but expected monkey label"))?;
+        if !monkey_label.starts_with("Monkey ") {
+            return Err(anyhow!("Expected monkey label but got {}", &monkey_label));
+        }
+        let start = lines
+            .next()
+            .ok_or_else(|| anyhow!("Raw monkey block ended but expected starting items line"))?;
+        let start_items = start.trim().trim_start_matches("Starting items: ");
+        for item in start_items.split(", ") {
+            items.push_back(
+                item.parse::<usize>()
+                    .map_err(|e| anyhow!("Failed to parse starting item {} -- {}", item, e))?,
+            );
+        }
+        let operation = lines
+            .next()
+            .ok_or_else(|| anyhow!("Raw monkey block ended but expected operation line"))?;
+        let mut op_pair = operation
+            .trim()
+            .trim_start_matches("Operation: new = old ")
+            .split(' ');
+        let op_ty = op_pair
+            .next()
+            .ok_or_else(|| anyhow!("Operation line over but expected operator type"))?;
+        let op_arg = op_pair
+            .next()
+            .ok_or_else(|| anyhow!("Operation line ended but expected operator argument"))?;
+        let operation = match (op_ty, op_arg) {
+            ("*", "old") => Operation::Square,
+            ("+", "old") => Operation::Double,
+            ("*", a) => Operation::Mul(a.parse::<usize>()?),
+            ("+", a) => Operation::Add(a.parse::<usize>()?),
+            (o, a) => return Err(anyhow!("Unexpected operator {} with target {}", o, a)),
+        };
+        let test_ln = lines
+            .next()
+            .ok_or_else(|| anyhow!("Raw monkey block ended but expected test case line"))?;
+        let divisor = test_ln
+            .trim()
+            .trim_start_matches("Test: divisible by ")
+            .parse::<usize>()?;
+        let true_case = lines
+            .next()
+            .ok_or_else(|| anyhow!("Raw monkey block ended but expected true test case"))?;
+        let true_monkey_idx = true_case
+            .trim()
+            .trim_start_matches("If true: throw to monkey ")
+            .parse::<usize>()?;
+        let false_case = lines
+            .next()
+            .ok_or_else(|| anyhow!("Raw monkey block ended but expected false test case"))?;
+        let false_monkey_idx = false_case
+            .trim()
+            .trim_start_matches("If false: throw to monkey ")
+            .parse::<usize>()?;
+        Ok(Monkey::new(
+            items,
+            operation,
+            divisor,
+            true_monkey_idx,
+            false_monkey_idx,
+        ))
+    }
+}
+
+/// All the monkeys, indexed by their label.
+#[derive(Debug, Clone)]
+pub struct MonkeyShow {
+    monkeys: Box<[Monkey]>,
+    test_product: usize,
+}
+
+impl MonkeyShow {
+    /// A show of `monkeys`, or an error if their divisors' product overflows.
+    pub fn new(monkeys: Box<[Monkey]>) -> Result<Self> {
+        let test_product =
+            checked::product(DAY, "divisor product", monkeys.iter().map(|v| v.divisor))?;
+        Ok(Self {
+            monkeys,
+            test_product,
+        })
+    }
+    /// Let every monkey take its turn once. With `relief` worry levels are divided by
+    /// three after each inspection, otherwise they are kept modulo the divisors' product.
+    pub fn exe_round(&mut self, relief: bool) -> Result<()> {
+        for midx in 0..self.monkeys.len() {
+            while let Some(mut item) = self.monkeys[midx].pop() {
+                self.monkeys[midx].inspection_count += 1;
+                item = match self.monkeys[midx].operation {
+                    Operation::Mul(a) => checked::mul(DAY, "worry level `old * n`", item, a)?,
+                    Operation::Add(a) => checked::add(DAY, "worry level `old + n`", item, a)?,
+                    Operation::Square => checked::mul(DAY, "worry level `old * old`", item, item)?,
+                    Operation::Double => checked::add(DAY, "worry level `old + old`", item, item)?,
+                };
+                if relief {
+                    item /= 3;
+                } else {
+                    item %= self.test_product;
+                }
+                let to_idx = if item % self.monkeys[midx].divisor == 0 {
+                    self.monkeys[midx].true_midx
+                } else {
+                    self.monkeys[midx].false_midx
+                };
+                self.monkeys[to_idx].push(item);
+            }
+        }
+        Ok(())
+    }
+    /// The product of the two highest inspection counts.
+    pub fn monkey_business(&self) -> Result<usize> {
+        let mut fst = 0usize;
+        let mut snd = 0usize;
+        for v in self.monkeys.iter().map(|v| v.inspection_count) {
+            if v > fst {
+                snd = fst;
+                fst = v;
+            } else if v > snd {
+                snd = v;
+            }
+        }
+        Ok(checked::mul(DAY, "monkey business", fst, snd)?)
+    }
+}
+
+/// Read `input` as the monkeys' starting state.
+pub fn read_initial_state(input: &str) -> Result<MonkeyShow> {
+    parse_initial_state(&std::fs::read_to_string(input)?)
+}
+
+/// Parse monkey blocks separated by blank lines.
+pub fn parse_initial_state(data: &str) -> Result<MonkeyShow> {
+    let mut monkeys = Vec::new();
+    for raw_monkey in data.split("\n\n") {
+        monkeys.push(Monkey::try_from_str(raw_monkey)?);
+    }
+    MonkeyShow::new(monkeys.into_boxed_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fuzz::check_parser;
+
+    use crate::{parse_initial_state, read_initial_state};
+
+    #[test]
+    fn test_part_one() {
+        let mut show = read_initial_state("src/test_input.txt").unwrap();
+        for _ in 0..20 {
+            show.exe_round(true).unwrap();
+        }
+        assert_eq!(10_605, show.monkey_business().unwrap());
+    }
+
+    #[test]
+    fn test_part_two() {
+        let mut show = read_initial_state("src/test_input.txt").unwrap();
+        for _ in 0..10_000 {
+            show.exe_round(false).unwrap();
+        }
+        assert_eq!(2_713_310_158, show.monkey_business().unwrap());
+    }
+
+    #[test]
+    fn test_fuzz_parsers() {
+        let base = std::fs::read_to_string("src/test_input.txt").unwrap();
+        check_parser("parse_initial_state", &base, 11, 2_000, parse_initial_state);
+    }
+}
@@ -0,0 +1,336 @@
+//! Day 12: Hill Climbing Algorithm.
+//!
+//! The input is a heightmap of letters `a-z`, with the start marked `S` and the best
+//! signal location marked `E`. Each square is a node in a graph whose edges are the
+//! allowed single steps.
+
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use anyhow::{anyhow, Result};
+
+/// A square of the heightmap: its graph node index and its `(row, col)`.
+#[derive(Debug, Copy, Clone)]
+pub struct Location {
+    pub node_idx: usize,
+    pub grid_idx: (usize, usize),
+}
+
+impl Location {
+    /// A square with node index `node_idx` at `grid_idx`.
+    pub fn new(node_idx: usize, grid_idx: (usize, usize)) -> Self {
+        Self { node_idx, grid_idx }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct State {
+    cost: usize,
+    position: usize,
+}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .cmp(&self.cost)
+            .then_with(|| self.position.cmp(&other.position))
+    }
+}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A step to `node` costing `cost`.
+#[derive(Debug, Copy, Clone)]
+pub struct Edge {
+    pub node: usize,
+    pub cost: usize,
+}
+
+/// The cost of a shortest path from any node in `starts` to a node satisfying `is_goal`,
+/// with the nodes along it, both ends included.
+pub fn dijkstra(
+    adj_list: &[Vec<Edge>],
+    starts: &[usize],
+    is_goal: impl Fn(usize) -> bool,
+) -> Option<(usize, Vec<usize>)> {
+    let mut dist: Vec<_> = (0..adj_list.len()).map(|_| usize::MAX).collect();
+    let mut prev: Vec<Option<usize>> = vec![None; adj_list.len()];
+    let mut heap = BinaryHeap::new();
+
+    for &start in starts {
+        dist[start] = 0;
+        heap.push(State {
+            cost: 0,
+            position: start,
+        });
+    }
+
+    while let Some(State { cost, position }) = heap.pop() {
+        if is_goal(position) {
+            let mut route = vec![position];
+            while let Some(p) = prev[route[route.len() - 1]] {
+                route.push(p);
+            }
+            route.reverse();
+            return Some((cost, route));
+        }
+        if cost > dist[position] {
+            continue;
+        }
+        for edge in &adj_list[position] {
+            let nxt = State {
+                cost: cost + edge.cost,
+                position: edge.node,
+            };
+            if nxt.cost < dist[nxt.position] {
+                heap.push(nxt);
+                dist[nxt.position] = nxt.cost;
+                prev[nxt.position] = Some(position);
+            }
+        }
+    }
+    None
+}
+
+/// The cost of the shortest path from `start` to `end`, if there is one.
+pub fn shortest_path(adj_list: &[Vec<Edge>], start: usize, end: usize) -> Option<usize> {
+    dijkstra(adj_list, &[start], |node| node == end).map(|(cost, _)| cost)
+}
+
+/// The nodes along a shortest path from `start` to `end`, both included.
+pub fn shortest_route(adj_list: &[Vec<Edge>], start: usize, end: usize) -> Option<Vec<usize>> {
+    dijkstra(adj_list, &[start], |node| node == end).map(|(_, route)| route)
+}
+
+/// The heightmap, with `S` and `E` stored as `a` and `z`.
+#[derive(Debug, Clone)]
+pub struct HeightMap {
+    grid: Vec<Vec<(Location, u8)>>,
+}
+
+impl HeightMap {
+    /// A heightmap over `grid`, whose rows must all have the same length.
+    pub fn new(grid: Vec<Vec<(Location, u8)>>) -> Self {
+        Self { grid }
+    }
+    /// The adjacency list of allowed steps, which climb at most one level at a time.
+    pub fn to_graph(&self) -> Vec<Vec<Edge>> {
+        let mut graph = Vec::new();
+        for ridx in 0..self.grid.len() {
+            for cidx in 0..self.grid[ridx].len() {
+                let mut nodes = Vec::new();
+                let (_, curr) = self.grid[ridx][cidx];
+                if ridx > 0 {
+                    let (
+                        Location {
+                            node_idx,
+                            grid_idx: _,
+                        },
+                        up,
+                    ) = self.grid[ridx - 1][cidx];
+                    if up as isize - curr as isize <= 1 {
+                        nodes.push(Edge {
+                            node: node_idx,
+                            cost: 1,
+                        });
+                    }
+                }
+                if ridx + 1 < self.grid.len() {
+                    let (
+                        Location {
+                            node_idx,
+                            grid_idx: _,
+                        },
+                        down,
+                    ) = self.grid[ridx + 1][cidx];
+                    if down as isize - curr as isize <= 1 {
+                        nodes.push(Edge {
+                            node: node_idx,
+                            cost: 1,
+                        });
+                    }
+                }
+                if cidx > 0 {
+                    let (
+                        Location {
+                            node_idx,
+                            grid_idx: _,
+                        },
+                        left,
+                    ) = self.grid[ridx][cidx - 1];
+                    if left as isize - curr as isize <= 1 {
+                        nodes.push(Edge {
+                            node: node_idx,
+                            cost: 1,
+                        });
+                    }
+                }
+                if cidx + 1 < self.grid[ridx].len() {
+                    let (
+                        Location {
+                            node_idx,
+                            grid_idx: _,
+                        },
+                        right,
+                    ) = self.grid[ridx][cidx + 1];
+                    if right as isize - curr as isize <= 1 {
+                        nodes.push(Edge {
+                            node: node_idx,
+                            cost: 1,
+                        });
+                    }
+                }
+                graph.push(nodes);
+            }
+        }
+        graph
+    }
+    /// Every square of height `target`.
+    pub fn find_targets(&self, target: u8) -> Vec<Location> {
+        self.grid
+            .iter()
+            .flatten()
+            .filter_map(|&(l, h)| if h == target { Some(l) } else { None })
+            .collect()
+    }
+    /// The heightmap drawn with an arrow on each square of `route` pointing to the
+    /// next one, and the last square marked `E`.
+    pub fn render_route(&self, route: &[usize]) -> String {
+        let mut canvas = self
+            .grid
+            .iter()
+            .map(|row| vec!['.'; row.len()])
+            .collect::<Vec<Vec<char>>>();
+        let grid_idxs = self
+            .grid
+            .iter()
+            .flatten()
+            .map(|(l, _)| l.grid_idx)
+            .collect::<Vec<(usize, usize)>>();
+        for (&from, &to) in route.iter().zip(route.iter().skip(1)) {
+            let (fr, fc) = grid_idxs[from];
+            let (tr, tc) = grid_idxs[to];
+            canvas[fr][fc] = match (tr.cmp(&fr), tc.cmp(&fc)) {
+                (Ordering::Less, _) => '^',
+                (Ordering::Greater, _) => 'v',
+                (_, Ordering::Less) => '<',
+                _ => '>',
+            };
+        }
+        if let Some(&end) = route.last() {
+            let (er, ec) = grid_idxs[end];
+            canvas[er][ec] = 'E';
+        }
+        let mut s = String::new();
+        for row in canvas {
+            s.extend(row);
+            s.push('\n');
+        }
+        s
+    }
+}
+
+/// Read `input` into its start, end and heightmap.
+pub fn read_heightmap(input: &str) -> Result<(Location, Location, HeightMap)> {
+    parse_heightmap(&std::fs::read_to_string(input)?)
+}
+
+/// Parse a heightmap, returning its start, end and heights.
+pub fn parse_heightmap(data: &str) -> Result<(Location, Location, HeightMap)> {
+    let mut grid: Vec<Vec<(Location, u8)>> = Vec::new();
+    let mut start_loc = Location::new(0, (0, 0));
+    let mut end_loc = Location::new(0, (0, 0));
+    let mut node_idx = 0;
+    for (ridx, line) in data.lines().enumerate() {
+        let mut row = Vec::new();
+        for (cidx, c) in line.chars().enumerate() {
+            if !c.is_ascii_alphabetic() {
+                return Err(anyhow!("Expected all ASCII alphabetic types, got {}", c));
+            }
+            if c == 'S' {
+                start_loc.node_idx = node_idx;
+                start_loc.grid_idx = (ridx, cidx);
+                row.push((start_loc, b'a'));
+            } else if c == 'E' {
+                end_loc.node_idx = node_idx;
+                end_loc.grid_idx = (ridx, cidx);
+                row.push((end_loc, b'z'));
+            } else {
+                row.push((Location::new(node_idx, (ridx, cidx)), c as u8));
+            }
+            node_idx += 1;
+        }
+        if let Some(prev) = grid.last() {
+            if row.len() != prev.len() {
+                return Err(anyhow!(
+                    "Row {} has {} columns, expected {}",
+                    ridx,
+                    row.len(),
+                    prev.len()
+                ));
+            }
+        }
+        grid.push(row);
+    }
+    Ok((start_loc, end_loc, HeightMap::new(grid)))
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fuzz::check_parser;
+
+    use crate::{dijkstra, parse_heightmap, read_heightmap, shortest_path, shortest_route};
+
+    #[test]
+    fn test_part_one() {
+        let (start, end, hmap) = read_heightmap("src/test_input.txt").unwrap();
+        let adj_list = hmap.to_graph();
+        assert_eq!(
+            31,
+            shortest_path(&adj_list, start.node_idx, end.node_idx).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_part_two() {
+        let (_, end, hmap) = read_heightmap("src/test_input.txt").unwrap();
+        let adj_list = hmap.to_graph();
+        let start_locs = hmap.find_targets(b'a');
+        assert_eq!(
+            29,
+            start_locs
+                .iter()
+                .filter_map(|&start| shortest_path(&adj_list, start.node_idx, end.node_idx))
+                .min()
+                .unwrap()
+        );
+        let starts = start_locs.iter().map(|l| l.node_idx).collect::<Vec<_>>();
+        let (cost, route) = dijkstra(&adj_list, &starts, |node| node == end.node_idx).unwrap();
+        assert_eq!(29, cost);
+        assert_eq!(30, route.len());
+        assert!(starts.contains(&route[0]));
+    }
+
+    #[test]
+    fn test_render_route() {
+        let (start, end, hmap) = read_heightmap("src/test_input.txt").unwrap();
+        let adj_list = hmap.to_graph();
+        let route = shortest_route(&adj_list, start.node_idx, end.node_idx).unwrap();
+        assert_eq!(31, route.len() - 1);
+        let render = hmap.render_route(&route);
+        assert_eq!(1, render.matches('E').count());
+        assert_eq!(31, render.chars().filter(|c| "<>^v".contains(*c)).count());
+    }
+
+    #[test]
+    fn test_fuzz_parsers() {
+        let base = std::fs::read_to_string("src/test_input.txt").unwrap();
+        check_parser("parse_heightmap", &base, 12, 2_000, |s| {
+            parse_heightmap(s).map(|(_, _, hmap)| hmap.to_graph())
+        });
+    }
+}
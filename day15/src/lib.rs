@@ -0,0 +1,357 @@
+//! Day 15: Beacon Exclusion Zone.
+//!
+//! Each line reports a sensor's position and the closest beacon to it, measured in
+//! Manhattan distance.
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+use anyhow::{anyhow, Error, Result};
+use common::checked;
+
+const DAY: &str = "day15";
+
+/// Read `input` as a list of sensor reports.
+pub fn read_sensors(input: &str) -> Result<Vec<Sensor>> {
+    parse_sensors(&std::fs::read_to_string(input)?)
+}
+
+/// Parse one `Sensor at x=.., y=..: closest beacon is at x=.., y=..` report per line.
+pub fn parse_sensors(data: &str) -> Result<Vec<Sensor>> {
+    let mut sensors = Vec::new();
+    for line in data.lines() {
+        let mut s = line.split(": ");
+        let sensor_info = s.next().ok_or_else(|| anyhow!("Expected sensor info"))?;
+        let sensor_loc: Location = sensor_info
+            .trim()
+            .trim_start_matches("Sensor at ")
+            .try_into()?;
+        let beacon_info = s
+            .next()
+            .ok_or_else(|| anyhow!("Expected closest beacon info"))?;
+        let beacon_loc: Location = beacon_info
+            .trim()
+            .trim_start_matches("closest beacon is at ")
+            .try_into()?;
+        sensors.push(Sensor::new(sensor_loc, Beacon::new(beacon_loc)));
+    }
+    Ok(sensors)
+}
+
+/// A point in the tunnels, with `y` increasing downwards.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Location {
+    /// The point `x,y`.
+    pub fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+    /// The distress beacon's tuning frequency, `x * 4_000_000 + y`.
+    pub fn tuning_frequency(&self) -> Result<isize> {
+        let x = checked::mul(DAY, "tuning frequency", self.x, 4_000_000)?;
+        Ok(checked::add(DAY, "tuning frequency", x, self.y)?)
+    }
+}
+
+impl TryFrom<&str> for Location {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut s = value.trim().split(", ");
+        let x = s
+            .next()
+            .ok_or_else(|| anyhow!("Expected `x` location"))?
+            .trim()
+            .trim_start_matches("x=")
+            .parse::<isize>()?;
+        let y = s
+            .next()
+            .ok_or_else(|| anyhow!("Expected `y` location"))?
+            .trim()
+            .trim_start_matches("y=")
+            .parse::<isize>()?;
+        Ok(Self { x, y })
+    }
+}
+
+impl Location {
+    /// The Manhattan distance to `other`.
+    pub fn l1_dist(self, other: Location) -> usize {
+        (self.x - other.x).unsigned_abs() + (self.y - other.y).unsigned_abs()
+    }
+}
+
+/// A beacon at a known location.
+#[derive(Debug, Copy, Clone)]
+pub struct Beacon {
+    pub loc: Location,
+}
+
+impl Beacon {
+    /// A beacon at `loc`.
+    pub fn new(loc: Location) -> Self {
+        Self { loc }
+    }
+}
+
+/// A sensor and the beacon closest to it.
+#[derive(Debug, Copy, Clone)]
+pub struct Sensor {
+    pub loc: Location,
+    pub nearest_beacon: Beacon,
+}
+
+impl Sensor {
+    /// A sensor at `loc` reporting `nearest_beacon`.
+    pub fn new(loc: Location, nearest_beacon: Beacon) -> Self {
+        Self {
+            loc,
+            nearest_beacon,
+        }
+    }
+    /// The distance to the closest beacon. No other beacon can be within it.
+    pub fn beacon_l1(&self) -> usize {
+        self.loc.l1_dist(self.nearest_beacon.loc)
+    }
+}
+
+/// Every sensor, and the set of known beacon locations.
+#[derive(Debug, Clone)]
+pub struct Tunnels {
+    sensors: Vec<Sensor>,
+    beacons: HashSet<Location>,
+}
+
+impl Tunnels {
+    /// The tunnels covered by `sensors`.
+    pub fn new(sensors: Vec<Sensor>) -> Self {
+        let mut beacons = HashSet::new();
+        for sensor in sensors.iter() {
+            let _ = beacons.insert(sensor.nearest_beacon.loc);
+        }
+        Self { sensors, beacons }
+    }
+    /// The smallest and largest `y` of any sensor or beacon.
+    pub fn row_info(&self) -> (isize, isize) {
+        let mut min = isize::MAX;
+        let mut max = isize::MIN;
+        for sensor in &self.sensors {
+            let ys = sensor.loc.y;
+            let yb = sensor.nearest_beacon.loc.y;
+            if ys < min {
+                min = ys;
+            } else if ys > max {
+                max = ys;
+            }
+            if yb < min {
+                min = yb;
+            } else if yb > max {
+                max = yb;
+            }
+        }
+        (min, max)
+    }
+
+    /// The smallest and largest `x` of any sensor or beacon.
+    pub fn col_info(&self) -> (isize, isize) {
+        let mut min = isize::MAX;
+        let mut max = isize::MIN;
+        for sensor in &self.sensors {
+            let xs = sensor.loc.x;
+            let xb = sensor.nearest_beacon.loc.x;
+            if xs < min {
+                min = xs;
+            } else if xs > max {
+                max = xs;
+            }
+            if xb < min {
+                min = xb;
+            } else if xb > max {
+                max = xb;
+            }
+        }
+        (min, max)
+    }
+
+    /// The number of positions in `row` that cannot hold a beacon, scanning `margin`
+    /// columns beyond the outermost sensor or beacon on each side.
+    pub fn count_impossible_in_row(&self, row: isize, margin: isize) -> usize {
+        let (col_min, col_max) = self.col_info();
+        let mut num_cannot_be_present = 0;
+        for col in (col_min - margin)..=(col_max + margin) {
+            if !self.beacon_possible(Location::new(col, row)) {
+                num_cannot_be_present += 1;
+            }
+        }
+        num_cannot_be_present
+    }
+
+    /// Whether a beacon could be at `loc`: either one is known to be there or no sensor
+    /// rules it out.
+    pub fn beacon_possible(&self, loc: Location) -> bool {
+        if self.beacons.contains(&loc) {
+            return true;
+        }
+        for sensor in &self.sensors {
+            if loc.l1_dist(sensor.loc) <= sensor.beacon_l1() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// The merged column ranges of `row`, clamped to `min..max`, that some sensor rules out.
+    pub fn impossible_ranges_in_row(
+        &self,
+        row: isize,
+        min: isize,
+        max: isize,
+    ) -> Option<Vec<Range<isize>>> {
+        let mut raw_ranges = Vec::new();
+        for s in self.sensors.iter() {
+            let dist_to_beacon = s.beacon_l1() as isize;
+            let dist_to_row = (s.loc.y - row).abs();
+            if dist_to_beacon >= dist_to_row {
+                let diff = dist_to_beacon - dist_to_row;
+                let start = std::cmp::max(s.loc.x - diff, min);
+                let end = std::cmp::min(s.loc.x + diff, max);
+                raw_ranges.push(start..end);
+            }
+        }
+        if raw_ranges.is_empty() {
+            None
+        } else {
+            raw_ranges.sort_unstable_by_key(|r| r.start);
+            let mut merged_ranges = vec![raw_ranges[0].clone()];
+            for rr in raw_ranges.into_iter().skip(1) {
+                let prev = merged_ranges.last_mut().expect("Empty range when merging!");
+                if rr.start + 1 > prev.end {
+                    merged_ranges.push(rr);
+                } else if rr.end > prev.end {
+                    prev.end = rr.end;
+                }
+            }
+            Some(merged_ranges)
+        }
+    }
+
+    /// The only position in the search area where an unknown beacon could be.
+    pub fn find_distress_beacon(
+        &self,
+        row_min: isize,
+        row_max: isize,
+        col_min: isize,
+        col_max: isize,
+    ) -> Option<Location> {
+        'outer: for row in row_min..=row_max {
+            let mut col = col_min;
+            if let Some(ranges) = self.impossible_ranges_in_row(row, row_min, row_max) {
+                for r in &ranges {
+                    if r.contains(&col) {
+                        let next = r.end + 1;
+                        if next > col_max {
+                            continue 'outer;
+                        } else {
+                            col = r.end + 1;
+                        }
+                    } else if !self.beacons.contains(&Location::new(col, row)) {
+                        return Some(Location::new(col, row));
+                    } else {
+                        col += 1;
+                        continue;
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// The area from `min` to `max` scaled down to `width` x `height` characters, with
+    /// ruled-out positions as `#`, sensors as `S`, beacons as `B` and `highlight` as `X`.
+    pub fn render_coverage(
+        &self,
+        min: Location,
+        max: Location,
+        (width, height): (usize, usize),
+        highlight: Option<Location>,
+    ) -> String {
+        let span_x = max.x - min.x + 1;
+        let span_y = max.y - min.y + 1;
+        let mut canvas = vec![vec!['.'; width]; height];
+        for (row, line) in canvas.iter_mut().enumerate() {
+            for (col, cell) in line.iter_mut().enumerate() {
+                let sample = Location::new(
+                    min.x + (2 * col as isize + 1) * span_x / (2 * width as isize),
+                    min.y + (2 * row as isize + 1) * span_y / (2 * height as isize),
+                );
+                if !self.beacon_possible(sample) {
+                    *cell = '#';
+                }
+            }
+        }
+        let mut mark = |loc: Location, c: char| {
+            if (min.x..=max.x).contains(&loc.x) && (min.y..=max.y).contains(&loc.y) {
+                let col = ((loc.x - min.x) * width as isize / span_x) as usize;
+                let row = ((loc.y - min.y) * height as isize / span_y) as usize;
+                canvas[row][col] = c;
+            }
+        };
+        for sensor in self.sensors.iter() {
+            mark(sensor.loc, 'S');
+            mark(sensor.nearest_beacon.loc, 'B');
+        }
+        if let Some(loc) = highlight {
+            mark(loc, 'X');
+        }
+        let mut s = String::new();
+        for line in canvas {
+            s.extend(line);
+            s.push('\n');
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fuzz::check_parser;
+
+    use crate::{parse_sensors, read_sensors, Location, Tunnels};
+
+    #[test]
+    fn test_part_one() {
+        let tunnels = Tunnels::new(read_sensors("src/test_input.txt").unwrap());
+        let (col_min, col_max) = tunnels.col_info();
+        let mut num_cannot_be_present = 0;
+        for col in col_min..=col_max {
+            if !tunnels.beacon_possible(Location::new(col, 10)) {
+                num_cannot_be_present += 1;
+            }
+        }
+        assert_eq!(26, num_cannot_be_present);
+    }
+
+    #[test]
+    fn test_part_two() {
+        let tunnels = Tunnels::new(read_sensors("src/test_input.txt").unwrap());
+        let col_min = 0isize;
+        let col_max = 20isize;
+        let row_min = 0isize;
+        let row_max = 20isize;
+        let tuning_frequency = tunnels
+            .find_distress_beacon(row_min, row_max, col_min, col_max)
+            .unwrap()
+            .tuning_frequency()
+            .unwrap();
+        assert_eq!(56_000_011, tuning_frequency);
+    }
+
+    #[test]
+    fn test_fuzz_parsers() {
+        let base = std::fs::read_to_string("src/test_input.txt").unwrap();
+        check_parser("parse_sensors", &base, 15, 2_000, parse_sensors);
+    }
+}